@@ -0,0 +1,260 @@
+//! Batched Account Fetching
+//!
+//! Reads many oracle accounts with chunked `getMultipleAccounts` calls and
+//! returns them as one snapshot, so every price in a refresh is decoded from
+//! the same slot.
+//!
+//! # Flow
+//! ```text
+//! [pyth feeds + switchboard aggregators]  (deduplicated)
+//!         ↓
+//! chunks of 100 → getMultipleAccounts (minContextSlot = highest slot seen)
+//!         ↓
+//! chunks behind the highest slot are re-read at that slot
+//!         ↓
+//! AccountSnapshot { slot, accounts }
+//! ```
+//!
+//! A refresh of N symbols costs `ceil(2N / 100)` calls in the common case.
+
+use crate::error::{OracleError, Result};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+/// Most accounts a node accepts in one `getMultipleAccounts` call
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Re-reads allowed to bring every chunk onto the same slot
+const MAX_SLOT_ALIGN_ATTEMPTS: usize = 3;
+
+/// Account data read at a single slot
+#[derive(Debug, Clone, Default)]
+pub struct AccountSnapshot {
+    /// Slot every account was read at
+    pub slot: u64,
+
+    /// Account data by address; accounts that don't exist are absent
+    accounts: HashMap<Pubkey, Vec<u8>>,
+}
+
+impl AccountSnapshot {
+    /// Data of `address`, if the account exists
+    pub fn get(&self, address: &Pubkey) -> Option<&[u8]> {
+        self.accounts.get(address).map(Vec::as_slice)
+    }
+
+    /// Add the accounts of `other`, keeping the higher slot
+    pub fn merge(&mut self, other: AccountSnapshot) {
+        self.slot = self.slot.max(other.slot);
+        self.accounts.extend(other.accounts);
+    }
+}
+
+/// Fetch `addresses` into one snapshot
+///
+/// The first chunk asks for `minContextSlot` = `min_context_slot`, every
+/// later one for the highest slot seen so far. Chunks answered at an older slot are re-read, so the whole
+/// snapshot comes from one slot. If the node keeps advancing, the snapshot
+/// is returned at the highest slot after `MAX_SLOT_ALIGN_ATTEMPTS`, with a
+/// warning.
+///
+/// # Arguments
+/// * `rpc_client` - Solana RPC client
+/// * `addresses` - Accounts to read (duplicates are fetched once)
+/// * `commitment` - Commitment level of the reads
+/// * `min_context_slot` - Oldest slot the node may answer at
+/// * `timeout` - Upper bound on each `getMultipleAccounts` call
+///
+/// # Errors
+/// * `SolanaError` - An RPC call failed (including a node behind
+///   `min_context_slot`)
+/// * `Timeout` - An RPC call took longer than `timeout`
+pub async fn fetch_snapshot(
+    rpc_client: &RpcClient,
+    addresses: &[Pubkey],
+    commitment: CommitmentConfig,
+    min_context_slot: Option<u64>,
+    timeout: Duration,
+) -> Result<AccountSnapshot> {
+    let chunks = plan_chunks(addresses);
+    let mut snapshot = AccountSnapshot {
+        slot: min_context_slot.unwrap_or(0),
+        ..AccountSnapshot::default()
+    };
+    let mut chunk_slots = Vec::with_capacity(chunks.len());
+
+    for chunk in &chunks {
+        let min_slot = (snapshot.slot > 0).then_some(snapshot.slot);
+        let slot = fetch_chunk(rpc_client, chunk, commitment, min_slot, timeout, &mut snapshot.accounts).await?;
+        snapshot.slot = snapshot.slot.max(slot);
+        chunk_slots.push(slot);
+    }
+
+    for _ in 0..MAX_SLOT_ALIGN_ATTEMPTS {
+        let behind: Vec<usize> = (0..chunks.len())
+            .filter(|&i| chunk_slots[i] < snapshot.slot)
+            .collect();
+        if behind.is_empty() {
+            break;
+        }
+
+        debug!("Re-reading {} chunk(s) at slot {}", behind.len(), snapshot.slot);
+        for i in behind {
+            let slot = fetch_chunk(
+                rpc_client, &chunks[i], commitment, Some(snapshot.slot), timeout, &mut snapshot.accounts,
+            ).await?;
+            snapshot.slot = snapshot.slot.max(slot);
+            chunk_slots[i] = slot;
+        }
+    }
+
+    if chunk_slots.iter().any(|&slot| slot != snapshot.slot) {
+        warn!(
+            "Account snapshot spans slots {:?}; using slot {}",
+            chunk_slots, snapshot.slot
+        );
+    }
+
+    Ok(snapshot)
+}
+
+/// Split addresses into request-sized chunks, dropping duplicates
+fn plan_chunks(addresses: &[Pubkey]) -> Vec<Vec<Pubkey>> {
+    let mut seen = HashSet::new();
+    let unique: Vec<Pubkey> = addresses.iter()
+        .filter(|address| seen.insert(**address))
+        .copied()
+        .collect();
+
+    unique.chunks(MAX_ACCOUNTS_PER_REQUEST)
+        .map(<[Pubkey]>::to_vec)
+        .collect()
+}
+
+/// One `getMultipleAccounts` call; returns the slot it was answered at
+async fn fetch_chunk(
+    rpc_client: &RpcClient,
+    chunk: &[Pubkey],
+    commitment: CommitmentConfig,
+    min_context_slot: Option<u64>,
+    timeout: Duration,
+    accounts: &mut HashMap<Pubkey, Vec<u8>>,
+) -> Result<u64> {
+    let config = RpcAccountInfoConfig {
+        commitment: Some(commitment),
+        min_context_slot,
+        ..RpcAccountInfoConfig::default()
+    };
+
+    let response = tokio::time::timeout(timeout, rpc_client.get_multiple_accounts_with_config(chunk, config))
+        .await
+        .map_err(|_| OracleError::Timeout(format!(
+            "getMultipleAccounts for {} accounts took longer than {:?}", chunk.len(), timeout
+        )))?
+        .map_err(|e| OracleError::SolanaError(format!(
+            "Failed to fetch accounts: {}", e
+        )))?;
+
+    for (address, account) in chunk.iter().zip(response.value) {
+        match account {
+            Some(account) => {
+                accounts.insert(*address, account.data);
+            }
+            None => {
+                accounts.remove(address);
+            }
+        }
+    }
+
+    Ok(response.context.slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    type MockState = (Arc<Mutex<std::vec::IntoIter<u64>>>, Arc<Mutex<Vec<Value>>>);
+
+    /// Answers every key with 3 bytes of data, at the next scripted slot
+    async fn get_multiple_accounts(
+        State((slots, requests)): State<MockState>,
+        Json(request): Json<Value>,
+    ) -> Json<Value> {
+        let slot = slots.lock().unwrap().next().unwrap();
+        let keys = request["params"][0].as_array().unwrap().len();
+        requests.lock().unwrap().push(request.clone());
+
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {
+                "context": { "slot": slot },
+                "value": (0..keys).map(|_| json!({
+                    "data": ["AQID", "base64"],
+                    "executable": false,
+                    "lamports": 1,
+                    "owner": "11111111111111111111111111111111",
+                    "rentEpoch": 0,
+                    "space": 3,
+                })).collect::<Vec<_>>(),
+            },
+        }))
+    }
+
+    /// Local JSON-RPC node; returns its URL and the requests it received
+    async fn mock_rpc(slots: Vec<u64>) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route("/", post(get_multiple_accounts))
+            .with_state((Arc::new(Mutex::new(slots.into_iter())), requests.clone()));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (url, requests)
+    }
+
+    #[test]
+    fn test_plan_chunks() {
+        let mut addresses: Vec<Pubkey> = (0..150).map(|_| Pubkey::new_unique()).collect();
+        let duplicates = addresses[..20].to_vec();
+        addresses.extend(duplicates);
+
+        let chunks = plan_chunks(&addresses);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), MAX_ACCOUNTS_PER_REQUEST);
+        assert_eq!(chunks[1].len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_aligns_slots() {
+        // Second chunk lands one slot later, so the first is re-read
+        let (url, requests) = mock_rpc(vec![10, 11, 11]).await;
+        let client = RpcClient::new(url);
+        let addresses: Vec<Pubkey> = (0..150).map(|_| Pubkey::new_unique()).collect();
+
+        let snapshot = fetch_snapshot(
+            &client, &addresses, CommitmentConfig::confirmed(), None, Duration::from_secs(5),
+        ).await.unwrap();
+
+        assert_eq!(snapshot.slot, 11);
+        assert_eq!(snapshot.get(&addresses[0]), Some(&[1, 2, 3][..]));
+        assert_eq!(snapshot.get(&addresses[149]), Some(&[1, 2, 3][..]));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0]["params"][1]["commitment"], "confirmed");
+        assert!(requests[0]["params"][1]["minContextSlot"].is_null());
+        assert_eq!(requests[1]["params"][1]["minContextSlot"], 10);
+        assert_eq!(requests[2]["params"][1]["minContextSlot"], 11);
+    }
+}
@@ -0,0 +1,397 @@
+//! Aggregation Strategies
+//!
+//! How `PriceAggregator` reduces validated source prices to one consensus
+//! price. Every strategy works on `oracle_consensus::Quote`s (8-decimal fixed
+//! point), so results are exact and reproducible.
+//!
+//! # Strategies
+//! ```text
+//! median              middle price; moves only if half the sources collude
+//! weighted_median     median by 1/confidence weight; tight quotes count more
+//! trimmed_mean:N      mean after dropping N% of quotes from each end
+//! priority:a>b>c      first listed source that has a price; median otherwise
+//! ```
+//!
+//! # Manipulation resistance
+//! * `median` - Bounded by the honest prices while manipulated quotes are
+//!   fewer than honest ones.
+//! * `weighted_median` - Bounded while manipulated weight is below honest
+//!   weight; a source reporting a tiny confidence outvotes everyone, so it
+//!   should only weigh sources whose confidence is trusted.
+//! * `trimmed_mean:N` - Bounded while manipulated quotes fit in the trimmed
+//!   tails; any quote that survives trimming pulls the mean.
+//! * `priority` - No resistance to the primary: the other sources only
+//!   constrain it through the deviation check.
+//!
+//! # Example
+//! ```rust
+//! let strategy: AggregationStrategy = "priority:pyth>switchboard".parse()?;
+//! let consensus = strategy.aggregate(&valid_prices)?;
+//! ```
+
+use crate::{
+    error::{OracleError, Result},
+    types::{PriceData, PriceSource},
+};
+use oracle_consensus::{self as consensus, Quote};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Confidence-weighted median weights are `WEIGHT_SCALE / confidence`
+const WEIGHT_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Rule for combining source prices into a consensus price
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// Plain median (matches the on-chain program)
+    #[default]
+    Median,
+
+    /// Median with each quote weighted by `1 / confidence`
+    ConfidenceWeightedMedian,
+
+    /// Mean of the quotes left after dropping `trim_percent`% from each end
+    TrimmedMean { trim_percent: u8 },
+
+    /// First source in the list that has a price, falling back to the median
+    SourcePriority(Vec<PriceSource>),
+}
+
+impl FromStr for AggregationStrategy {
+    type Err = OracleError;
+
+    fn from_str(strategy: &str) -> Result<Self> {
+        let strategy = strategy.trim().to_lowercase();
+        let (name, argument) = strategy.split_once(':').unwrap_or((strategy.as_str(), ""));
+
+        match name {
+            "median" => Ok(AggregationStrategy::Median),
+            "weighted_median" | "confidence_weighted_median" => {
+                Ok(AggregationStrategy::ConfidenceWeightedMedian)
+            }
+            "trimmed_mean" => {
+                let trim_percent: u8 = argument.parse()
+                    .ok()
+                    .filter(|&percent| percent < 50)
+                    .ok_or_else(|| OracleError::ParseError(format!(
+                        "trimmed_mean needs a trim percent below 50, got {:?}", argument
+                    )))?;
+                Ok(AggregationStrategy::TrimmedMean { trim_percent })
+            }
+            "priority" => {
+                let sources = argument.split('>')
+                    .map(|source| parse_source(source.trim()))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(AggregationStrategy::SourcePriority(sources))
+            }
+            _ => Err(OracleError::ParseError(format!("Unknown aggregation strategy: {}", strategy))),
+        }
+    }
+}
+
+fn parse_source(source: &str) -> Result<PriceSource> {
+    match source {
+        "pyth" => Ok(PriceSource::Pyth),
+        "switchboard" => Ok(PriceSource::Switchboard),
+        "hermes" => Ok(PriceSource::Hermes),
+        "cex" => Ok(PriceSource::Cex),
+        "amm" => Ok(PriceSource::Amm),
+        _ => Err(OracleError::ParseError(format!("Unknown price source: {}", source))),
+    }
+}
+
+impl AggregationStrategy {
+    /// Combine `prices` (all of one symbol) into a consensus price
+    ///
+    /// The result has source `Aggregate` and the newest slot of the inputs.
+    ///
+    /// # Errors
+    /// * `NoPriceData` - `prices` is empty
+    /// * `ParseError` - A price doesn't fit the fixed-point range
+    pub fn aggregate(&self, prices: &[PriceData]) -> Result<PriceData> {
+        let first = prices.first()
+            .ok_or_else(|| OracleError::NoPriceData("No prices to aggregate".to_string()))?;
+
+        let quotes = prices.iter()
+            .map(PriceData::to_quote)
+            .collect::<Result<Vec<Quote>>>()?;
+        let sources: Vec<PriceSource> = prices.iter().map(|p| p.source).collect();
+        let quote = self.aggregate_quotes(&quotes, &sources)?;
+
+        Ok(PriceData {
+            slot: prices.iter().filter_map(|p| p.slot).max(),
+            ..PriceData::from_quote(&first.symbol, &quote, PriceSource::Aggregate)?
+        })
+    }
+
+    /// Combine `quotes`, where `sources[i]` produced `quotes[i]`
+    fn aggregate_quotes(&self, quotes: &[Quote], sources: &[PriceSource]) -> Result<Quote> {
+        match self {
+            AggregationStrategy::Median => Ok(consensus::median(quotes)?.quote),
+            AggregationStrategy::ConfidenceWeightedMedian => weighted_median(quotes),
+            AggregationStrategy::TrimmedMean { trim_percent } => trimmed_mean(quotes, *trim_percent),
+            AggregationStrategy::SourcePriority(priority) => {
+                let primary = priority.iter()
+                    .find_map(|source| sources.iter().position(|s| s == source));
+
+                match primary {
+                    Some(index) => Ok(quotes[index]),
+                    None => Ok(consensus::median(quotes)?.quote),
+                }
+            }
+        }
+    }
+}
+
+/// Quotes sorted by price
+fn sorted(quotes: &[Quote]) -> Result<Vec<Quote>> {
+    let first = quotes.first().ok_or(consensus::ConsensusError::NoPriceData)?;
+    if quotes.iter().any(|q| q.expo != first.expo) {
+        return Err(consensus::ConsensusError::ExponentMismatch.into());
+    }
+
+    let mut sorted = quotes.to_vec();
+    sorted.sort_by_key(|q| q.price);
+    Ok(sorted)
+}
+
+/// Lowest quote at which the cumulative `1 / confidence` weight reaches half
+/// the total
+///
+/// A zero confidence counts as one unit, so it carries the largest weight
+/// without dividing by zero.
+fn weighted_median(quotes: &[Quote]) -> Result<Quote> {
+    let sorted = sorted(quotes)?;
+    let weight = |q: &Quote| WEIGHT_SCALE / q.confidence.max(1) as u128;
+
+    let total: u128 = sorted.iter().map(weight).sum();
+    let mut cumulative = 0;
+    for quote in &sorted {
+        cumulative += weight(quote);
+        if cumulative * 2 >= total {
+            return Ok(*quote);
+        }
+    }
+
+    unreachable!("cumulative weight reaches the total")
+}
+
+/// Mean of the quotes left after dropping `trim_percent`% from each end
+///
+/// At least one quote is always kept. Confidence is the mean of the kept
+/// confidences and the timestamp the newest kept one.
+fn trimmed_mean(quotes: &[Quote], trim_percent: u8) -> Result<Quote> {
+    let sorted = sorted(quotes)?;
+    let trim = (sorted.len() * trim_percent as usize / 100).min((sorted.len() - 1) / 2);
+    let kept = &sorted[trim..sorted.len() - trim];
+
+    let count = kept.len() as i128;
+    let price = kept.iter().map(|q| q.price as i128).sum::<i128>() / count;
+    let confidence = kept.iter().map(|q| q.confidence as i128).sum::<i128>() / count;
+
+    Ok(Quote {
+        price: price as i64,
+        confidence: confidence as u64,
+        expo: kept[0].expo,
+        timestamp: kept.iter().map(|q| q.timestamp).max().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn quote(price: i64, confidence: u64) -> Quote {
+        Quote { price, confidence, expo: -8, timestamp: 1_700_000_000 }
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!("median".parse::<AggregationStrategy>().unwrap(), AggregationStrategy::Median);
+        assert_eq!(
+            "weighted_median".parse::<AggregationStrategy>().unwrap(),
+            AggregationStrategy::ConfidenceWeightedMedian
+        );
+        assert_eq!(
+            "trimmed_mean:20".parse::<AggregationStrategy>().unwrap(),
+            AggregationStrategy::TrimmedMean { trim_percent: 20 }
+        );
+        assert_eq!(
+            "priority:pyth>switchboard".parse::<AggregationStrategy>().unwrap(),
+            AggregationStrategy::SourcePriority(vec![PriceSource::Pyth, PriceSource::Switchboard])
+        );
+
+        assert!("trimmed_mean:50".parse::<AggregationStrategy>().is_err());
+        assert!("priority:pyth>binance".parse::<AggregationStrategy>().is_err());
+        assert!("mean".parse::<AggregationStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_weighted_median() {
+        // The tight quote carries more weight than the two wide ones together
+        let quotes = [quote(100, 10), quote(200, 1), quote(300, 10)];
+        assert_eq!(weighted_median(&quotes).unwrap().price, 200);
+
+        let quotes = [quote(100, 1), quote(200, 10), quote(300, 10)];
+        assert_eq!(weighted_median(&quotes).unwrap().price, 100);
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        let quotes = [quote(100, 2), quote(110, 4), quote(120, 6), quote(1_000_000, 8)];
+
+        // 25% of 4 trims one quote from each end
+        let mean = trimmed_mean(&quotes, 25).unwrap();
+        assert_eq!(mean.price, 115);
+        assert_eq!(mean.confidence, 5);
+
+        // Never trims everything
+        assert_eq!(trimmed_mean(&quotes[..1], 49).unwrap().price, 100);
+    }
+
+    #[test]
+    fn test_source_priority() {
+        let strategy = AggregationStrategy::SourcePriority(vec![PriceSource::Amm, PriceSource::Cex]);
+        let quotes = [quote(100, 1), quote(200, 1), quote(300, 1)];
+
+        let sources = [PriceSource::Pyth, PriceSource::Cex, PriceSource::Amm];
+        assert_eq!(strategy.aggregate_quotes(&quotes, &sources).unwrap().price, 300);
+
+        // Primary missing: next in line
+        let sources = [PriceSource::Pyth, PriceSource::Cex, PriceSource::Switchboard];
+        assert_eq!(strategy.aggregate_quotes(&quotes, &sources).unwrap().price, 200);
+
+        // None listed: median
+        let sources = [PriceSource::Pyth, PriceSource::Hermes, PriceSource::Switchboard];
+        assert_eq!(strategy.aggregate_quotes(&quotes, &sources).unwrap().price, 200);
+    }
+
+    // ========================================================================
+    // MANIPULATION RESISTANCE PROPERTIES
+    // ========================================================================
+
+    /// Honest quotes around $50,000 and attacker quotes anywhere
+    fn honest_quotes(count: std::ops::Range<usize>) -> impl Strategy<Value = Vec<Quote>> {
+        prop::collection::vec(
+            (4_900_000_000_000i64..5_100_000_000_000, 1u64..100_000_000).prop_map(|(p, c)| quote(p, c)),
+            count,
+        )
+    }
+
+    fn attacker_prices(count: std::ops::Range<usize>) -> impl Strategy<Value = Vec<i64>> {
+        prop::collection::vec(1i64..i64::MAX / 4, count)
+    }
+
+    fn within_honest_range(result: &Quote, honest: &[Quote]) -> bool {
+        let min = honest.iter().map(|q| q.price).min().unwrap();
+        let max = honest.iter().map(|q| q.price).max().unwrap();
+        (min..=max).contains(&result.price)
+    }
+
+    proptest! {
+        /// Fewer attackers than honest sources can't leave the honest range
+        #[test]
+        fn prop_median_bounded_by_honest_majority(
+            honest in honest_quotes(3..9),
+            attackers in attacker_prices(0..3),
+        ) {
+            prop_assume!(attackers.len() < honest.len());
+            let quotes: Vec<Quote> = honest.iter().copied()
+                .chain(attackers.iter().map(|&p| quote(p, 1_000_000)))
+                .collect();
+
+            let median = consensus::median(&quotes).unwrap().quote;
+            prop_assert!(within_honest_range(&median, &honest));
+        }
+
+        /// Attackers with less total weight than the honest sources can't
+        /// leave the honest range, however far off they quote
+        #[test]
+        fn prop_weighted_median_bounded_by_honest_weight(
+            honest in honest_quotes(1..6),
+            attackers in attacker_prices(1..6),
+        ) {
+            // Each attacker is as wide as the widest honest quote, and there
+            // are fewer attackers than honest quotes
+            let widest = honest.iter().map(|q| q.confidence).max().unwrap();
+            let attackers = &attackers[..attackers.len().min(honest.len().saturating_sub(1))];
+            let quotes: Vec<Quote> = honest.iter().copied()
+                .chain(attackers.iter().map(|&p| quote(p, widest)))
+                .collect();
+
+            let result = weighted_median(&quotes).unwrap();
+            prop_assert!(within_honest_range(&result, &honest));
+        }
+
+        /// One attacker claiming near-zero confidence wins the weighted
+        /// median outright: confidence must come from trusted sources
+        #[test]
+        fn prop_weighted_median_follows_overconfident_source(
+            honest in honest_quotes(1..6),
+            attacker in 1i64..i64::MAX / 4,
+        ) {
+            let quotes: Vec<Quote> = honest.iter().copied()
+                .chain(std::iter::once(quote(attacker, 0)))
+                .collect();
+
+            // 1e18 / 1 outweighs any honest 1e18 / c with c >= 1 unless
+            // honest weights add up to as much
+            let honest_weight: u128 = honest.iter().map(|q| WEIGHT_SCALE / q.confidence as u128).sum();
+            prop_assume!(honest_weight < WEIGHT_SCALE);
+
+            prop_assert_eq!(weighted_median(&quotes).unwrap().price, attacker);
+        }
+
+        /// Attackers that fit in the trimmed tails can't leave the honest range
+        #[test]
+        fn prop_trimmed_mean_bounded_when_outliers_trimmed(
+            honest in honest_quotes(4..12),
+            attackers in attacker_prices(0..4),
+            trim_percent in 10u8..50,
+        ) {
+            let total = honest.len() + attackers.len();
+            prop_assume!(attackers.len() <= total * trim_percent as usize / 100);
+            let quotes: Vec<Quote> = honest.iter().copied()
+                .chain(attackers.iter().map(|&p| quote(p, 1_000_000)))
+                .collect();
+
+            let result = trimmed_mean(&quotes, trim_percent).unwrap();
+            prop_assert!(within_honest_range(&result, &honest));
+        }
+
+        /// Without trimming, a single attacker moves the mean by 1/n of
+        /// its offset, so one far enough away leaves the honest range
+        #[test]
+        fn prop_untrimmed_mean_moved_by_one_outlier(
+            honest in honest_quotes(2..8),
+        ) {
+            let max = honest.iter().map(|q| q.price).max().unwrap();
+            let attacker = max + (honest.len() as i64 + 1) * 1_000_000_000_000;
+            let quotes: Vec<Quote> = honest.iter().copied()
+                .chain(std::iter::once(quote(attacker, 1_000_000)))
+                .collect();
+
+            let result = trimmed_mean(&quotes, 0).unwrap();
+            prop_assert!(!within_honest_range(&result, &honest));
+        }
+
+        /// The primary source sets the price whatever the others quote
+        #[test]
+        fn prop_priority_follows_primary(
+            primary in 1i64..i64::MAX / 4,
+            others in attacker_prices(0..5),
+        ) {
+            let strategy = AggregationStrategy::SourcePriority(vec![PriceSource::Pyth]);
+            let quotes: Vec<Quote> = std::iter::once(quote(primary, 1))
+                .chain(others.iter().map(|&p| quote(p, 1)))
+                .collect();
+            let sources: Vec<PriceSource> = std::iter::once(PriceSource::Pyth)
+                .chain(others.iter().map(|_| PriceSource::Switchboard))
+                .collect();
+
+            prop_assert_eq!(strategy.aggregate_quotes(&quotes, &sources).unwrap().price, primary);
+        }
+    }
+}
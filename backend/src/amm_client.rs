@@ -0,0 +1,566 @@
+//! AMM Pool Client
+//!
+//! Prices long-tail Solana tokens that only trade in AMM pools, by decoding
+//! pool state read through the Solana RPC client.
+//!
+//! # Pool kinds
+//! ```text
+//! Whirlpool (Orca, concentrated liquidity)     one account
+//!   tick_spacing       u16  @ 41
+//!   liquidity          u128 @ 49    in-range liquidity L
+//!   sqrt_price         u128 @ 65    √(B per A) as Q64.64
+//!   tick_current_index i32  @ 81
+//!   → price = (sqrt_price / 2^64)²
+//!
+//! Constant product (Raydium AMM, Orca legacy)  two SPL token vaults
+//!   amount     u64  @ 64    vault balance
+//!   → price = reserve B / reserve A, L = √(reserve A × reserve B)
+//! ```
+//!
+//! # Confidence
+//! Pools have no confidence interval, so it's derived from depth: the
+//! price impact of buying with `probe_size` quote units. Within a range of
+//! constant liquidity L, paying in Δy of token B moves √price by Δy / L
+//! (and Δx of token A moves 1/√price by Δx / L), so
+//! ```text
+//! √p' = √p + probe_size / L        (quote-side √price)
+//! confidence = p' − p
+//! ```
+//!
+//! Pools whose quote-side depth is below `min_liquidity` are rejected
+//! outright; a thin pool is the cheapest oracle to move. A constant-product
+//! pool's depth is its quote vault. A whirlpool's L only holds up to the
+//! next initializable tick, where positions may end, so its depth is what a
+//! trade can pay in before reaching that tick — not the virtual reserve
+//! L·√p, which a narrow position inflates.
+use crate::{
+    config::CommitmentPolicy,
+    error::{OracleError, Result},
+    oracle_source::OracleSource,
+    rpc_pool::RpcPool,
+    types::{FeedStatus, PriceData, PriceSource},
+};
+use async_trait::async_trait;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+use tracing::{debug, warn};
+
+/// Anchor discriminator of Whirlpool accounts: `sha256("account:Whirlpool")[..8]`
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+
+/// Whirlpool `liquidity: u128` offset (after discriminator, config, bump,
+/// tick spacing, seed and fee rates)
+const WHIRLPOOL_LIQUIDITY_OFFSET: usize = 49;
+
+/// Whirlpool `sqrt_price: u128` offset
+const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+
+/// Whirlpool `tick_spacing: u16` offset (after discriminator, config and bump)
+const WHIRLPOOL_TICK_SPACING_OFFSET: usize = 41;
+
+/// Whirlpool `tick_current_index: i32` offset
+const WHIRLPOOL_TICK_CURRENT_OFFSET: usize = 81;
+
+/// Price ratio between adjacent ticks
+const TICK_BASE: f64 = 1.0001;
+
+/// SPL token account `amount: u64` offset (after mint and owner)
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+
+/// Where a pool keeps its price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolKind {
+    /// Concentrated liquidity: price and liquidity in the pool account
+    Whirlpool { pool: Pubkey },
+
+    /// Constant product: price is the ratio of the two vault balances
+    ConstantProduct { vault_a: Pubkey, vault_b: Pubkey },
+}
+
+/// One pool priced as a symbol
+#[derive(Debug, Clone, Copy)]
+pub struct PoolConfig {
+    pub kind: PoolKind,
+
+    /// Decimals of the pool's token A mint
+    pub decimals_a: u8,
+
+    /// Decimals of the pool's token B mint
+    pub decimals_b: u8,
+
+    /// Token A is the symbol's quote (pool is e.g. USDC/BONK for BONK/USD)
+    pub invert: bool,
+}
+
+impl FromStr for PoolConfig {
+    type Err = OracleError;
+
+    /// Parse `whirlpool:<pool>:<decimals a>:<decimals b>[:invert]` or
+    /// `cpmm:<vault a>:<vault b>:<decimals a>:<decimals b>[:invert]`
+    fn from_str(spec: &str) -> Result<Self> {
+        let invalid = || OracleError::ParseError(format!("Invalid pool spec: {}", spec));
+        let pubkey = |s: &str| Pubkey::from_str(s)
+            .map_err(|e| OracleError::ParseError(format!("Invalid pubkey: {}", e)));
+        let decimals = |s: &str| s.parse::<u8>().map_err(|_| invalid());
+
+        let mut parts: Vec<&str> = spec.split(':').collect();
+        let invert = parts.last() == Some(&"invert");
+        if invert {
+            parts.pop();
+        }
+
+        let (kind, decimals_a, decimals_b) = match parts.as_slice() {
+            ["whirlpool", pool, a, b] => (PoolKind::Whirlpool { pool: pubkey(pool)? }, *a, *b),
+            ["cpmm", vault_a, vault_b, a, b] => (
+                PoolKind::ConstantProduct { vault_a: pubkey(vault_a)?, vault_b: pubkey(vault_b)? },
+                *a,
+                *b,
+            ),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            kind,
+            decimals_a: decimals(decimals_a)?,
+            decimals_b: decimals(decimals_b)?,
+            invert,
+        })
+    }
+}
+
+/// Price, liquidity and depth of a pool, in whole tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PoolState {
+    /// √(token B per token A)
+    sqrt_price: f64,
+
+    /// Liquidity L: paying in Δy of B moves √price by Δy / L, paying in
+    /// Δx of A moves 1/√price by Δx / L
+    liquidity: f64,
+
+    /// Token A a buyer can pay in at this liquidity
+    depth_a: f64,
+
+    /// Token B a buyer can pay in at this liquidity
+    depth_b: f64,
+}
+
+/// Price source decoding AMM pool accounts
+pub struct AmmClient {
+    rpc_pool: Arc<RpcPool>,
+    pools: HashMap<String, PoolConfig>,
+
+    /// Pools with less quote-side depth are rejected (quote units)
+    min_liquidity: Decimal,
+
+    /// Trade size whose price impact is reported as confidence (quote units)
+    probe_size: Decimal,
+
+    /// Upper bound on a single RPC request
+    timeout: Duration,
+
+    /// Commitment of account reads
+    commitment: CommitmentPolicy,
+}
+
+impl AmmClient {
+    /// Create a new client
+    ///
+    /// # Arguments
+    /// * `rpc_url` - Solana RPC endpoint
+    /// * `min_liquidity` - Liquidity floor, in quote units
+    /// * `probe_size` - Trade size used for confidence, in quote units
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn new(rpc_url: &str, min_liquidity: Decimal, probe_size: Decimal, timeout: Duration) -> Self {
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url, timeout)), min_liquidity, probe_size, timeout)
+    }
+
+    /// Create a client reading through a shared RPC pool
+    ///
+    /// # Arguments
+    /// * `rpc_pool` - Health-scored Solana RPC endpoints
+    /// * `min_liquidity` - Liquidity floor, in quote units
+    /// * `probe_size` - Trade size used for confidence, in quote units
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn with_pool(
+        rpc_pool: Arc<RpcPool>,
+        min_liquidity: Decimal,
+        probe_size: Decimal,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            rpc_pool,
+            pools: HashMap::new(),
+            min_liquidity,
+            probe_size,
+            timeout,
+            commitment: CommitmentPolicy::default(),
+        }
+    }
+
+    /// Read accounts at the commitment `policy` gives this source
+    pub fn set_commitment(&mut self, policy: CommitmentPolicy) {
+        self.commitment = policy;
+    }
+
+    /// Price `symbol` from `pool`
+    ///
+    /// # Example
+    /// ```rust
+    /// // BONK/USD from a BONK/USDC whirlpool
+    /// amm.register_pool("BONK/USD".to_string(), "whirlpool:<pool address>:5:6".parse()?);
+    /// ```
+    pub fn register_pool(&mut self, symbol: String, pool: PoolConfig) {
+        debug!("Registered AMM pool for {}: {:?}", symbol, pool.kind);
+        self.pools.insert(symbol, pool);
+    }
+
+    fn pool(&self, symbol: &str) -> Result<&PoolConfig> {
+        self.pools.get(symbol).ok_or_else(|| OracleError::NoPriceData(
+            format!("No AMM pool registered for {}", symbol)
+        ))
+    }
+
+    /// Turn pool state into a price, applying the liquidity floor
+    ///
+    /// Confidence is the probe's price impact, `(√p + probe / L)² − p` on
+    /// the quote side. The depth must cover the probe too, or the impact
+    /// would run past the liquidity it was computed with.
+    ///
+    /// The slot is left unset: pool accounts don't record when they last
+    /// changed, so the price is as of the read. `get_price` stamps the
+    /// snapshot slot; batched reads and subscriptions stamp the slot of
+    /// their snapshot or notification.
+    fn to_price_data(&self, symbol: &str, pool: &PoolConfig, state: PoolState) -> Result<PriceData> {
+        // Quote-side √price: buying with quote always raises it
+        let (sqrt_price, depth) = if pool.invert {
+            (1.0 / state.sqrt_price, state.depth_a)
+        } else {
+            (state.sqrt_price, state.depth_b)
+        };
+
+        let to_decimal = |value: f64| Decimal::try_from(value)
+            .map(|d| d.round_dp(12))
+            .map_err(|e| OracleError::InvalidPrice(format!("AMM price for {} out of range: {}", symbol, e)));
+        let depth = to_decimal(depth)?;
+
+        let floor = self.min_liquidity.max(self.probe_size);
+        if depth <= Decimal::ZERO || depth < floor {
+            return Err(OracleError::LowLiquidity(format!(
+                "{} pool has {} quote units of depth (min: {})",
+                symbol, depth, floor
+            )));
+        }
+
+        let probe = self.probe_size.to_f64().unwrap_or_default();
+        let price = sqrt_price * sqrt_price;
+        let moved = sqrt_price + probe / state.liquidity;
+
+        Ok(PriceData {
+            symbol: symbol.to_string(),
+            price: to_decimal(price)?,
+            confidence: to_decimal(moved * moved - price)?,
+            // Pool state is current as of the read
+            timestamp: chrono::Utc::now().timestamp(),
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Amm,
+        })
+    }
+
+    /// Decode a Whirlpool account into pool state
+    ///
+    /// Depth runs to the initializable ticks either side of the current
+    /// tick: buying A with B pushes √price up to the upper one, buying B
+    /// with A down to the lower one.
+    ///
+    /// # Errors
+    /// * `ParseError` - Not a Whirlpool account (wrong discriminator), so a
+    ///   misconfigured address can't decode into a plausible price
+    fn decode_whirlpool(data: &[u8], decimals_a: u8, decimals_b: u8) -> Result<PoolState> {
+        if !data.starts_with(&WHIRLPOOL_DISCRIMINATOR) {
+            return Err(OracleError::ParseError("Account is not a Whirlpool".to_string()));
+        }
+
+        let liquidity = read_u128(data, WHIRLPOOL_LIQUIDITY_OFFSET)?;
+        let sqrt_price_x64 = read_u128(data, WHIRLPOOL_SQRT_PRICE_OFFSET)?;
+        let tick_spacing = read_u16(data, WHIRLPOOL_TICK_SPACING_OFFSET)? as i32;
+        let tick_current = read_i32(data, WHIRLPOOL_TICK_CURRENT_OFFSET)?;
+        if sqrt_price_x64 == 0 {
+            return Err(OracleError::InvalidPrice("Whirlpool has no price".to_string()));
+        }
+        if tick_spacing == 0 {
+            return Err(OracleError::ParseError("Whirlpool has no tick spacing".to_string()));
+        }
+
+        // √(raw B per raw A) now and at the surrounding initializable ticks
+        let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+        let tick_lower = tick_current.div_euclid(tick_spacing) * tick_spacing;
+        let sqrt_lower = TICK_BASE.powf(tick_lower as f64 / 2.0);
+        let sqrt_upper = TICK_BASE.powf((tick_lower + tick_spacing) as f64 / 2.0);
+        let liquidity = liquidity as f64;
+
+        // Raw to whole tokens: √price scales by 10^((a - b) / 2), L by 10^(-(a + b) / 2)
+        let sqrt_scale = 10f64.powf((decimals_a as f64 - decimals_b as f64) / 2.0);
+        Ok(PoolState {
+            sqrt_price: sqrt_price * sqrt_scale,
+            liquidity: liquidity / 10f64.powf((decimals_a as f64 + decimals_b as f64) / 2.0),
+            depth_a: liquidity * (1.0 / sqrt_lower - 1.0 / sqrt_price).max(0.0) / 10f64.powi(decimals_a as i32),
+            depth_b: liquidity * (sqrt_upper - sqrt_price).max(0.0) / 10f64.powi(decimals_b as i32),
+        })
+    }
+
+    /// Pool state from the two vault balances of a constant-product pool
+    fn constant_product_state(vault_a: &[u8], vault_b: &[u8], decimals_a: u8, decimals_b: u8) -> Result<PoolState> {
+        let reserve_a = read_u64(vault_a, TOKEN_AMOUNT_OFFSET)? as f64 / 10f64.powi(decimals_a as i32);
+        let reserve_b = read_u64(vault_b, TOKEN_AMOUNT_OFFSET)? as f64 / 10f64.powi(decimals_b as i32);
+        if reserve_a == 0.0 || reserve_b == 0.0 {
+            return Err(OracleError::LowLiquidity("Pool has an empty vault".to_string()));
+        }
+
+        // A constant-product pool is one position over the whole price range
+        Ok(PoolState {
+            sqrt_price: (reserve_b / reserve_a).sqrt(),
+            liquidity: (reserve_a * reserve_b).sqrt(),
+            depth_a: reserve_a,
+            depth_b: reserve_b,
+        })
+    }
+}
+
+#[async_trait]
+impl OracleSource for AmmClient {
+    fn name(&self) -> &'static str {
+        "AMM"
+    }
+
+    fn source(&self) -> PriceSource {
+        PriceSource::Amm
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.pools.keys().cloned().collect()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        let pool = self.pool(symbol)?;
+        let addresses = match pool.kind {
+            PoolKind::Whirlpool { pool } => vec![pool],
+            PoolKind::ConstantProduct { vault_a, vault_b } => vec![vault_a, vault_b],
+        };
+
+        // Both vaults must come from the same slot
+        let commitment = self.commitment.resolve(self.name(), symbol);
+        let snapshot = self.rpc_pool.fetch_snapshot(&addresses, commitment, self.timeout).await?;
+        let account = |address: &Pubkey| snapshot.get(address).ok_or_else(|| OracleError::NoPriceData(
+            format!("AMM account {} not found", address)
+        ));
+
+        let state = match pool.kind {
+            PoolKind::Whirlpool { pool: address } => {
+                Self::decode_whirlpool(account(&address)?, pool.decimals_a, pool.decimals_b)?
+            }
+            PoolKind::ConstantProduct { vault_a, vault_b } => Self::constant_product_state(
+                account(&vault_a)?, account(&vault_b)?, pool.decimals_a, pool.decimals_b,
+            )?,
+        };
+
+        let price = PriceData {
+            slot: Some(snapshot.slot),
+            ..self.to_price_data(symbol, pool, state)?
+        };
+        debug!("AMM price for {} at slot {}: ${} ±${}", symbol, snapshot.slot, price.price, price.confidence);
+
+        Ok(price)
+    }
+
+    async fn health_check(&self) -> bool {
+        let Some(symbol) = self.pools.keys().next() else {
+            warn!("No AMM pools registered for health check");
+            return false;
+        };
+
+        match self.get_price(symbol).await {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("AMM health check failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Whirlpools price from one account, so they join batched snapshots
+    /// and subscriptions; constant-product pools need two and don't
+    fn account_address(&self, symbol: &str) -> Option<Pubkey> {
+        match self.pools.get(symbol)?.kind {
+            PoolKind::Whirlpool { pool } => Some(pool),
+            PoolKind::ConstantProduct { .. } => None,
+        }
+    }
+
+    fn decode_account(&self, symbol: &str, data: &[u8]) -> Result<PriceData> {
+        let pool = self.pool(symbol)?;
+        let state = Self::decode_whirlpool(data, pool.decimals_a, pool.decimals_b)?;
+        self.to_price_data(symbol, pool, state)
+    }
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
+    data.get(offset..offset + 16)
+        .map(|bytes| u128::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| OracleError::ParseError(format!("Pool account too short ({} bytes)", data.len())))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| OracleError::ParseError(format!("Pool account too short ({} bytes)", data.len())))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| OracleError::ParseError(format!("Pool account too short ({} bytes)", data.len())))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| OracleError::ParseError(format!("Token account too short ({} bytes)", data.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SOL/USDC whirlpool at $150 with $1,000,000 of virtual USDC reserve,
+    /// and initializable ticks every `tick_spacing`
+    fn whirlpool_bytes(tick_spacing: u16) -> Vec<u8> {
+        // Raw price: 150 × 10^(6 - 9) USDC base units per lamport
+        let sqrt_price = 0.15f64.sqrt();
+        let liquidity = 1e12 / sqrt_price;
+        let tick_current = (0.15f64.ln() / TICK_BASE.ln()).floor() as i32;
+
+        let mut data = vec![0u8; 653];
+        data[..8].copy_from_slice(&WHIRLPOOL_DISCRIMINATOR);
+        data[WHIRLPOOL_TICK_SPACING_OFFSET..][..2].copy_from_slice(&tick_spacing.to_le_bytes());
+        data[WHIRLPOOL_LIQUIDITY_OFFSET..][..16].copy_from_slice(&(liquidity as u128).to_le_bytes());
+        data[WHIRLPOOL_SQRT_PRICE_OFFSET..][..16].copy_from_slice(&((sqrt_price * 2f64.powi(64)) as u128).to_le_bytes());
+        data[WHIRLPOOL_TICK_CURRENT_OFFSET..][..4].copy_from_slice(&tick_current.to_le_bytes());
+        data
+    }
+
+    fn token_account(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[TOKEN_AMOUNT_OFFSET..][..8].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    fn client(min_liquidity: i64) -> AmmClient {
+        AmmClient::new("http://localhost", Decimal::from(min_liquidity), Decimal::from(1_000), Duration::from_secs(5))
+    }
+
+    #[test]
+    fn test_whirlpool_price() {
+        let mut amm = client(50_000);
+        amm.register_pool("SOL/USD".to_string(), PoolConfig {
+            kind: PoolKind::Whirlpool { pool: Pubkey::new_unique() },
+            decimals_a: 9,
+            decimals_b: 6,
+            invert: false,
+        });
+
+        let price = amm.decode_account("SOL/USD", &whirlpool_bytes(16_384)).unwrap();
+        assert_eq!(price.price.round_dp(6), Decimal::from(150));
+        // $1,000 moves √price by 0.1%: 150 × (1.001² − 1)
+        assert_eq!(price.confidence.round_dp(6), Decimal::new(30015, 5));
+        assert_eq!(price.source, PriceSource::Amm);
+        assert_eq!(price.slot, None);
+        assert!(amm.account_address("SOL/USD").is_some());
+
+        assert!(matches!(amm.decode_account("SOL/USD", &[0u8; 64]), Err(OracleError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_whirlpool_narrow_range() {
+        let mut amm = client(50_000);
+        amm.register_pool("SOL/USD".to_string(), "whirlpool:11111111111111111111111111111111:9:6".parse().unwrap());
+
+        // Same $1,000,000 virtual reserve, but L only holds for 64 ticks
+        // (~0.6%), about $1,400 of USDC before the next tick
+        let state = AmmClient::decode_whirlpool(&whirlpool_bytes(64), 9, 6).unwrap();
+        assert!(state.depth_b > 1_000.0 && state.depth_b < 3_200.0);
+        assert!(matches!(
+            amm.decode_account("SOL/USD", &whirlpool_bytes(64)),
+            Err(OracleError::LowLiquidity(_))
+        ));
+
+        let mut amm = client(1_000);
+        amm.register_pool("SOL/USD".to_string(), "whirlpool:11111111111111111111111111111111:9:6".parse().unwrap());
+        assert!(amm.decode_account("SOL/USD", &whirlpool_bytes(64)).is_ok());
+    }
+
+    #[test]
+    fn test_constant_product_price() {
+        let amm = client(50_000);
+        let pool = PoolConfig {
+            kind: PoolKind::ConstantProduct { vault_a: Pubkey::new_unique(), vault_b: Pubkey::new_unique() },
+            decimals_a: 6,
+            decimals_b: 5,
+            invert: true,
+        };
+
+        // 200,000 USDC against 10,000,000,000 BONK: BONK = $0.00002
+        let state = AmmClient::constant_product_state(
+            &token_account(200_000_000_000),
+            &token_account(1_000_000_000_000_000),
+            pool.decimals_a,
+            pool.decimals_b,
+        ).unwrap();
+        let price = amm.to_price_data("BONK/USD", &pool, state).unwrap();
+
+        assert_eq!(price.price.round_dp(10), Decimal::new(2, 5));
+        // $1,000 into $200,000 moves √price by 0.5%: 0.00002 × (1.005² − 1)
+        assert_eq!(price.confidence.round_dp(12), Decimal::new(2005, 10));
+    }
+
+    #[test]
+    fn test_liquidity_floor() {
+        let amm = client(50_000);
+        let pool: PoolConfig = format!("cpmm:{}:{}:9:6", Pubkey::new_unique(), Pubkey::new_unique()).parse().unwrap();
+
+        // 100 SOL against $15,000
+        let state = AmmClient::constant_product_state(
+            &token_account(100_000_000_000),
+            &token_account(15_000_000_000),
+            pool.decimals_a,
+            pool.decimals_b,
+        ).unwrap();
+
+        assert!(matches!(amm.to_price_data("SOL/USD", &pool, state), Err(OracleError::LowLiquidity(_))));
+        assert!(client(10_000).to_price_data("SOL/USD", &pool, state).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_non_whirlpool_account() {
+        // Right size and a sane-looking price, but some other account type
+        let mut data = whirlpool_bytes(64);
+        data[..8].copy_from_slice(&[0; 8]);
+        assert!(matches!(AmmClient::decode_whirlpool(&data, 9, 6), Err(OracleError::ParseError(_))));
+
+        assert!(matches!(AmmClient::decode_whirlpool(&WHIRLPOOL_DISCRIMINATOR[..4], 9, 6), Err(OracleError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_pool_spec() {
+        let pool = Pubkey::new_unique();
+        let config: PoolConfig = format!("whirlpool:{}:5:6:invert", pool).parse().unwrap();
+        assert_eq!(config.kind, PoolKind::Whirlpool { pool });
+        assert_eq!((config.decimals_a, config.decimals_b, config.invert), (5, 6, true));
+
+        assert!("whirlpool:not-a-key:5:6".parse::<PoolConfig>().is_err());
+        assert!(format!("cpmm:{}:9:6", pool).parse::<PoolConfig>().is_err());
+    }
+}
@@ -6,8 +6,10 @@ use crate::{
     error::{OracleError, Result},
     types::PriceData,
     price_aggregator::PriceAggregator,
+    switchboard_client::{AggregatorInfo, SwitchboardClient},
     cache::CachedPriceFetcher,
     database::Database,
+    rpc_pool::{EndpointHealth, RpcPool},
 };
 use axum::{
     extract::{Path, Query, State},
@@ -28,6 +30,8 @@ pub struct AppState {
     pub aggregator: Arc<Mutex<PriceAggregator>>,
     pub cache: Arc<Mutex<CachedPriceFetcher>>,
     pub db: Arc<Database>,
+    pub switchboard: Arc<SwitchboardClient>,
+    pub rpc_pool: Arc<RpcPool>,
 }
 
 /// Create the API router
@@ -43,6 +47,9 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/v1/health", get(health_check))
         .route("/api/v1/health/oracles", get(oracle_health))
         
+        // Oracle detail endpoints
+        .route("/api/v1/oracles/switchboard/:symbol", get(switchboard_info))
+        
         // Admin endpoints
         .route("/api/v1/admin/cache/clear", post(clear_cache))
         .route("/api/v1/admin/cache/stats", get(cache_stats))
@@ -69,23 +76,7 @@ async fn get_price(
     
     info!("Fetching price for {}", symbol);
     
-    let price = {
-        let mut cache = state.cache.lock().await;
-        let aggregator = state.aggregator.lock().await;
-        
-        cache.get_price_with_cache(
-            &symbol,
-            |s| {
-                let agg = aggregator.clone();
-                async move {
-                    agg.get_consensus_price(&s).await
-                }
-            }
-        ).await?
-    };
-    
-    // Store in database for history
-    state.db.insert_price(&price).await?;
+    let price = get_price_internal(&state, &symbol).await?;
     
     Ok(Json(PriceResponse {
         success: true,
@@ -98,6 +89,11 @@ async fn get_price(
 /// 
 /// Get current prices for multiple symbols
 /// 
+/// Cached symbols are served from Redis; all misses are fetched together
+/// from one batched account snapshot. If the batched read fails, each miss
+/// is fetched on its own. Cache and database errors are logged, not
+/// returned, and a symbol without a price is left out.
+/// 
 /// Query params: ?symbols=BTC-USD,ETH-USD,SOL-USD
 async fn get_all_prices(
     State(state): State<AppState>,
@@ -111,16 +107,63 @@ async fn get_all_prices(
     info!("Fetching prices for {} symbols", symbols.len());
     
     let mut prices = Vec::new();
+    let mut fetched = Vec::new();
     
-    for symbol in symbols {
-        match get_price_internal(&state, &symbol).await {
-            Ok(price) => prices.push(price),
+    {
+        let mut cache = state.cache.lock().await;
+        let aggregator = state.aggregator.lock().await;
+        
+        let cached = match cache.cache().get_prices(&symbols).await {
+            Ok(cached) => cached,
             Err(e) => {
-                tracing::warn!("Failed to fetch {}: {}", symbol, e);
+                tracing::warn!("Cache read failed, fetching all symbols: {}", e);
+                vec![None; symbols.len()]
+            }
+        };
+        let misses: Vec<String> = symbols.iter()
+            .zip(&cached)
+            .filter(|(_, price)| price.is_none())
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        prices.extend(cached.into_iter().flatten());
+        
+        if !misses.is_empty() {
+            let results: Vec<(String, Result<PriceData>)> = match aggregator.get_consensus_prices(&misses).await {
+                Ok(snapshot) => snapshot.prices.into_iter().collect(),
+                Err(e) => {
+                    tracing::warn!("Batched price read failed, fetching symbols one by one: {}", e);
+                    let mut results = Vec::new();
+                    for symbol in &misses {
+                        results.push((symbol.clone(), aggregator.get_consensus_price(symbol).await));
+                    }
+                    results
+                }
+            };
+            
+            for (symbol, result) in results {
+                match result {
+                    Ok(price) => {
+                        if let Err(e) = cache.cache().set_price(&price).await {
+                            tracing::warn!("Failed to cache {}: {}", symbol, e);
+                        }
+                        fetched.push(price);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch {}: {}", symbol, e);
+                    }
+                }
             }
         }
     }
     
+    // Store fresh prices in database for history
+    for price in &fetched {
+        if let Err(e) = state.db.insert_price(price).await {
+            tracing::warn!("Failed to store {} price: {}", price.symbol, e);
+        }
+    }
+    prices.extend(fetched);
+    
     Ok(Json(MultiPriceResponse {
         success: true,
         data: prices,
@@ -203,6 +246,10 @@ async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
         oracles: oracle_health.into_iter()
             .map(|(source, health)| (format!("{:?}", source), health.is_healthy))
             .collect(),
+        sources: aggregator.fetch_stats().into_iter()
+            .map(|(source, stats)| (format!("{:?}", source), stats))
+            .collect(),
+        rpc_endpoints: state.rpc_pool.health(),
         timestamp: chrono::Utc::now().timestamp(),
     })
 }
@@ -219,6 +266,30 @@ async fn oracle_health(State(state): State<AppState>) -> Result<Json<OracleHealt
     }))
 }
 
+// ============================================================================
+// ORACLE DETAIL ENDPOINTS
+// ============================================================================
+
+/// GET /api/v1/oracles/switchboard/:symbol
+/// 
+/// Switchboard feed configuration and latest round: queue, variance
+/// threshold, force-report period and which oracles responded
+/// 
+/// Example: GET /api/v1/oracles/switchboard/BTC-USD
+async fn switchboard_info(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> Result<Json<SwitchboardInfoResponse>> {
+    let symbol = symbol.replace("-", "/");
+    let info = state.switchboard.get_aggregator_info(&symbol).await?;
+    
+    Ok(Json(SwitchboardInfoResponse {
+        success: true,
+        data: info,
+        timestamp: chrono::Utc::now().timestamp(),
+    }))
+}
+
 // ============================================================================
 // ADMIN ENDPOINTS
 // ============================================================================
@@ -326,6 +397,8 @@ pub struct HealthResponse {
     pub database: bool,
     pub cache: bool,
     pub oracles: std::collections::HashMap<String, bool>,
+    pub sources: std::collections::HashMap<String, crate::types::SourceFetchStats>,
+    pub rpc_endpoints: Vec<EndpointHealth>,
     pub timestamp: i64,
 }
 
@@ -335,6 +408,13 @@ pub struct OracleHealthResponse {
     pub data: Vec<crate::database::OracleHealthRecord>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SwitchboardInfoResponse {
+    pub success: bool,
+    pub data: AggregatorInfo,
+    pub timestamp: i64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AdminResponse {
     pub success: bool,
@@ -357,6 +437,13 @@ impl IntoResponse for OracleError {
             OracleError::NoPriceData(msg) => (StatusCode::NOT_FOUND, msg),
             OracleError::StalePrice(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
             OracleError::PriceDeviation(msg) => (StatusCode::CONFLICT, msg),
+            OracleError::InvalidPrice(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::LowLiquidity(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::PythNotTrading(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::InsufficientOracleQuorum(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::InsufficientSources(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::SourceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            OracleError::Timeout(msg) => (StatusCode::GATEWAY_TIMEOUT, msg),
             OracleError::DatabaseError(e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
             }
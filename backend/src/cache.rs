@@ -494,7 +494,7 @@ impl CachedPriceFetcher {
 mod tests {
     use super::*;
     use rust_decimal::Decimal;
-    use crate::types::PriceSource;
+    use crate::types::{FeedStatus, PriceSource};
 
     // Helper to create test price data
     fn create_test_price(symbol: &str, price: i64) -> PriceData {
@@ -503,6 +503,9 @@ mod tests {
             price: Decimal::from(price),
             confidence: Decimal::from(100),
             timestamp: chrono::Utc::now().timestamp(),
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
             source: PriceSource::Aggregate,
         }
     }
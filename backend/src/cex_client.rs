@@ -0,0 +1,474 @@
+//! Centralized-Exchange Reference Client
+//!
+//! Builds a reference price from exchange order books. Each configured venue
+//! is asked for its top of book over REST; the venue mids are combined into
+//! one `PriceData` whose confidence comes from the quoted spreads.
+//!
+//! # Pricing
+//! ```text
+//! venue mid     = (best bid + best ask) / 2
+//! half spread   = (best ask - best bid) / 2
+//!
+//! price         = median(venue mids)
+//! confidence    = median(half spreads) + |furthest mid - price|
+//! ```
+//!
+//! The dispersion term keeps confidence honest when venues disagree even
+//! though each book is tight.
+//!
+//! # Markets
+//! A symbol is read from a venue's `BASE/QUOTE` book unless a market is
+//! configured for it (`add_market`). Binance lists no USD books for most
+//! pairs, so it only prices USD symbols through an explicit market such as
+//! `BTCUSDT`, which quotes in USDT rather than USD.
+//!
+//! # Role
+//! Exchange prices aren't on-chain and can't be verified by the program, so
+//! by default the source is a `SourceRole::SanityCheck`: it doesn't vote in
+//! the median, but consensus is rejected when it strays too far from it.
+
+use crate::{
+    error::{OracleError, Result},
+    oracle_source::{OracleSource, SourceRole},
+    types::{FeedStatus, PriceData, PriceSource},
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::{collections::HashMap, str::FromStr, time::Duration};
+use tracing::{debug, warn};
+
+/// Exchange API a venue speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VenueKind {
+    /// `GET /api/v3/ticker/bookTicker?symbol=BTCUSDT`
+    Binance,
+    /// `GET /products/BTC-USD/ticker`
+    Coinbase,
+    /// `GET /0/public/Ticker?pair=BTCUSD`
+    Kraken,
+}
+
+impl VenueKind {
+    /// Default venue market for a "BASE/QUOTE" symbol
+    ///
+    /// # Errors
+    /// * `NoPriceData` - USD symbol on Binance; its USD-like books quote in
+    ///   stablecoins, so they must be configured explicitly
+    fn market(self, symbol: &str) -> Result<String> {
+        let (base, quote) = symbol.split_once('/').ok_or_else(|| OracleError::ParseError(
+            format!("Symbol {} is not BASE/QUOTE", symbol)
+        ))?;
+
+        Ok(match self {
+            VenueKind::Binance if quote == "USD" => return Err(OracleError::NoPriceData(format!(
+                "No Binance market configured for {}", symbol
+            ))),
+            VenueKind::Binance | VenueKind::Kraken => format!("{}{}", base, quote),
+            VenueKind::Coinbase => format!("{}-{}", base, quote),
+        })
+    }
+}
+
+impl FromStr for VenueKind {
+    type Err = OracleError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "binance" => Ok(VenueKind::Binance),
+            "coinbase" => Ok(VenueKind::Coinbase),
+            "kraken" => Ok(VenueKind::Kraken),
+            _ => Err(OracleError::ParseError(format!("Unknown exchange venue: {}", name))),
+        }
+    }
+}
+
+/// One exchange to read order books from
+#[derive(Debug, Clone)]
+struct Venue {
+    kind: VenueKind,
+
+    /// REST base URL (e.g. "https://api.binance.com")
+    base_url: String,
+}
+
+/// Best bid and ask of one venue
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TopOfBook {
+    bid: Decimal,
+    ask: Decimal,
+}
+
+impl TopOfBook {
+    fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+
+    fn half_spread(&self) -> Decimal {
+        (self.ask - self.bid) / Decimal::TWO
+    }
+}
+
+/// Reference price source built from exchange order books
+pub struct CexClient {
+    http: reqwest::Client,
+    venues: Vec<Venue>,
+    symbols: Vec<String>,
+    role: SourceRole,
+
+    /// Configured markets, overriding `VenueKind::market`
+    markets: HashMap<(VenueKind, String), String>,
+
+    /// Upper bound on a single venue request
+    timeout: Duration,
+}
+
+impl CexClient {
+    /// Create a client with no venues
+    ///
+    /// # Arguments
+    /// * `role` - Whether the reference price votes or only sanity-checks
+    /// * `timeout` - Upper bound on a single venue request
+    pub fn new(role: SourceRole, timeout: Duration) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| OracleError::ParseError(format!("Invalid HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http,
+            venues: Vec::new(),
+            symbols: Vec::new(),
+            role,
+            markets: HashMap::new(),
+            timeout,
+        })
+    }
+
+    /// Add a venue by name ("binance", "coinbase", "kraken") and REST base URL
+    pub fn add_venue(&mut self, name: &str, base_url: &str) -> Result<()> {
+        let kind = name.parse()?;
+        self.venues.push(Venue {
+            kind,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        });
+
+        debug!("Added {:?} venue at {}", kind, base_url);
+        Ok(())
+    }
+
+    /// Read `symbol` from `market` on every venue named `name`
+    ///
+    /// # Example
+    /// ```rust
+    /// // Accept USDT as USD on Binance
+    /// cex.add_market("binance", "BTC/USD", "BTCUSDT")?;
+    /// ```
+    pub fn add_market(&mut self, name: &str, symbol: &str, market: &str) -> Result<()> {
+        let kind = name.parse()?;
+        self.markets.insert((kind, symbol.to_string()), market.to_string());
+
+        debug!("Reading {} from {:?} market {}", symbol, kind, market);
+        Ok(())
+    }
+
+    /// Venue market for `symbol`: the configured one, else the default
+    fn market(&self, kind: VenueKind, symbol: &str) -> Result<String> {
+        match self.markets.get(&(kind, symbol.to_string())) {
+            Some(market) => Ok(market.clone()),
+            None => kind.market(symbol),
+        }
+    }
+
+    /// Price `symbol` from every venue
+    pub fn register_symbol(&mut self, symbol: String) {
+        if !self.symbols.contains(&symbol) {
+            self.symbols.push(symbol);
+        }
+    }
+
+    /// Top of book of `symbol` on one venue
+    async fn fetch_book(&self, venue: &Venue, symbol: &str) -> Result<TopOfBook> {
+        let market = self.market(venue.kind, symbol)?;
+        let request = match venue.kind {
+            VenueKind::Binance => self.http
+                .get(format!("{}/api/v3/ticker/bookTicker", venue.base_url))
+                .query(&[("symbol", &market)]),
+            VenueKind::Coinbase => self.http
+                .get(format!("{}/products/{}/ticker", venue.base_url, market)),
+            VenueKind::Kraken => self.http
+                .get(format!("{}/0/public/Ticker", venue.base_url))
+                .query(&[("pair", &market)]),
+        };
+
+        let response = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| if e.is_timeout() {
+                OracleError::Timeout(format!("{:?} took longer than {:?}", venue.kind, self.timeout))
+            } else {
+                OracleError::SolanaError(format!("{:?} request failed: {}", venue.kind, e))
+            })?;
+
+        let book = match venue.kind {
+            VenueKind::Binance => {
+                let ticker: BinanceBookTicker = parse_body(response).await?;
+                TopOfBook { bid: parse_decimal(&ticker.bid_price)?, ask: parse_decimal(&ticker.ask_price)? }
+            }
+            VenueKind::Coinbase => {
+                let ticker: CoinbaseTicker = parse_body(response).await?;
+                TopOfBook { bid: parse_decimal(&ticker.bid)?, ask: parse_decimal(&ticker.ask)? }
+            }
+            VenueKind::Kraken => {
+                let ticker: KrakenTicker = parse_body(response).await?;
+                if !ticker.error.is_empty() {
+                    return Err(OracleError::NoPriceData(format!("Kraken: {:?}", ticker.error)));
+                }
+                let pair = ticker.result.into_values().next().ok_or_else(|| OracleError::NoPriceData(
+                    format!("Kraken returned no book for {}", market)
+                ))?;
+                let best = |side: &[String]| parse_decimal(side.first().map_or("", String::as_str));
+                TopOfBook { bid: best(&pair.b)?, ask: best(&pair.a)? }
+            }
+        };
+
+        if book.bid <= Decimal::ZERO || book.ask < book.bid {
+            return Err(OracleError::InvalidPrice(format!(
+                "{:?} book for {} is empty or crossed: bid {} / ask {}",
+                venue.kind, symbol, book.bid, book.ask
+            )));
+        }
+
+        Ok(book)
+    }
+}
+
+#[async_trait]
+impl OracleSource for CexClient {
+    fn name(&self) -> &'static str {
+        "CEX"
+    }
+
+    fn source(&self) -> PriceSource {
+        PriceSource::Cex
+    }
+
+    fn role(&self) -> SourceRole {
+        self.role
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.symbols.clone()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        let books = futures::future::join_all(
+            self.venues.iter().map(|venue| self.fetch_book(venue, symbol))
+        ).await;
+
+        let books: Vec<TopOfBook> = books.into_iter()
+            .zip(&self.venues)
+            .filter_map(|(book, venue)| match book {
+                Ok(book) => Some(book),
+                Err(e) => {
+                    warn!("{:?} book for {} unavailable: {}", venue.kind, symbol, e);
+                    None
+                }
+            })
+            .collect();
+
+        if books.is_empty() {
+            return Err(OracleError::NoPriceData(format!("No exchange books for {}", symbol)));
+        }
+
+        let price = median(books.iter().map(TopOfBook::mid).collect());
+        let half_spread = median(books.iter().map(TopOfBook::half_spread).collect());
+        let dispersion = books.iter()
+            .map(|book| (book.mid() - price).abs())
+            .max()
+            .unwrap_or_default();
+
+        debug!("CEX price for {} from {} venues: ${}", symbol, books.len(), price);
+
+        Ok(PriceData {
+            symbol: symbol.to_string(),
+            price,
+            confidence: half_spread + dispersion,
+            timestamp: chrono::Utc::now().timestamp(),
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Cex,
+        })
+    }
+
+    /// Healthy while any venue quotes the first registered symbol
+    async fn health_check(&self) -> bool {
+        let Some(symbol) = self.symbols.first() else {
+            warn!("No CEX symbols registered for health check");
+            return false;
+        };
+
+        match self.get_price(symbol).await {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("CEX health check failed: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Median of unsorted values; `values` must not be empty
+fn median(mut values: Vec<Decimal>) -> Decimal {
+    values.sort();
+    let mid = values.len() / 2;
+
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / Decimal::TWO
+    } else {
+        values[mid]
+    }
+}
+
+// ============================================================================
+// VENUE RESPONSES
+// ============================================================================
+
+// Venues quote prices as strings to keep their precision
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceBookTicker {
+    bid_price: String,
+    ask_price: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseTicker {
+    bid: String,
+    ask: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenTicker {
+    error: Vec<String>,
+    #[serde(default)]
+    result: HashMap<String, KrakenPair>,
+}
+
+/// `a` / `b` are `[price, whole lot volume, lot volume]`
+#[derive(Debug, Deserialize)]
+struct KrakenPair {
+    a: Vec<String>,
+    b: Vec<String>,
+}
+
+async fn parse_body<T: for<'de> Deserialize<'de>>(response: reqwest::Response) -> Result<T> {
+    response.json().await
+        .map_err(|e| OracleError::ParseError(format!("Invalid exchange response: {}", e)))
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .map_err(|e| OracleError::ParseError(format!("Invalid exchange price {:?}: {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+    use serde_json::{json, Value};
+
+    /// One server playing all three venues; Kraken is down
+    async fn mock_venues() -> String {
+        async fn binance() -> Json<Value> {
+            Json(json!({ "symbol": "BTCUSDT", "bidPrice": "49990.00", "bidQty": "1.5", "askPrice": "50010.00", "askQty": "2" }))
+        }
+
+        async fn coinbase(Path(market): Path<String>) -> std::result::Result<Json<Value>, StatusCode> {
+            match market.as_str() {
+                "BTC-USD" => Ok(Json(json!({ "bid": "50095.00", "ask": "50105.00", "price": "50100.00", "volume": "10" }))),
+                // Crossed book
+                "ETH-USD" => Ok(Json(json!({ "bid": "3010.00", "ask": "3000.00", "price": "3005.00", "volume": "10" }))),
+                _ => Err(StatusCode::NOT_FOUND),
+            }
+        }
+
+        async fn kraken() -> StatusCode {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+
+        let app = Router::new()
+            .route("/api/v3/ticker/bookTicker", get(binance))
+            .route("/products/:market/ticker", get(coinbase))
+            .route("/0/public/Ticker", get(kraken));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn test_reference_price_from_venues() {
+        let url = mock_venues().await;
+        let mut client = CexClient::new(SourceRole::SanityCheck, Duration::from_secs(5)).unwrap();
+        for venue in ["binance", "coinbase", "kraken"] {
+            client.add_venue(venue, &url).unwrap();
+        }
+        client.add_market("binance", "BTC/USD", "BTCUSDT").unwrap();
+        client.register_symbol("BTC/USD".to_string());
+
+        let price = client.get_price("BTC/USD").await.unwrap();
+
+        // Mids 50,000 and 50,100; half spreads 10 and 5
+        assert_eq!(price.price, Decimal::from(50_050));
+        assert_eq!(price.confidence, Decimal::new(575, 1));
+        assert_eq!(price.source, PriceSource::Cex);
+        assert_eq!(client.role(), SourceRole::SanityCheck);
+    }
+
+    #[tokio::test]
+    async fn test_crossed_book_rejected() {
+        let url = mock_venues().await;
+        let mut client = CexClient::new(SourceRole::Voter, Duration::from_secs(5)).unwrap();
+        client.add_venue("coinbase", &url).unwrap();
+
+        assert!(matches!(client.fetch_book(&client.venues[0], "ETH/USD").await, Err(OracleError::InvalidPrice(_))));
+        assert!(matches!(client.get_price("ETH/USD").await, Err(OracleError::NoPriceData(_))));
+    }
+
+    #[tokio::test]
+    async fn test_binance_usd_requires_market() {
+        let url = mock_venues().await;
+        let mut client = CexClient::new(SourceRole::SanityCheck, Duration::from_secs(5)).unwrap();
+        for venue in ["binance", "coinbase"] {
+            client.add_venue(venue, &url).unwrap();
+        }
+
+        // Without a configured market only Coinbase quotes BTC/USD
+        let price = client.get_price("BTC/USD").await.unwrap();
+        assert_eq!(price.price, Decimal::from(50_100));
+        assert_eq!(price.confidence, Decimal::from(5));
+    }
+
+    #[test]
+    fn test_venue_markets() {
+        assert!(matches!(VenueKind::Binance.market("BTC/USD"), Err(OracleError::NoPriceData(_))));
+        assert_eq!(VenueKind::Binance.market("ETH/BTC").unwrap(), "ETHBTC");
+        assert_eq!(VenueKind::Coinbase.market("BTC/USD").unwrap(), "BTC-USD");
+        assert_eq!(VenueKind::Kraken.market("ETH/EUR").unwrap(), "ETHEUR");
+        assert!(VenueKind::Kraken.market("BTCUSD").is_err());
+        assert!("bitmex".parse::<VenueKind>().is_err());
+
+        let mut client = CexClient::new(SourceRole::SanityCheck, Duration::from_secs(5)).unwrap();
+        client.add_market("binance", "BTC/USD", "BTCUSDT").unwrap();
+        assert_eq!(client.market(VenueKind::Binance, "BTC/USD").unwrap(), "BTCUSDT");
+        assert!(client.market(VenueKind::Binance, "ETH/USD").is_err());
+        assert_eq!(client.market(VenueKind::Coinbase, "BTC/USD").unwrap(), "BTC-USD");
+        assert!(client.add_market("bitmex", "BTC/USD", "XBTUSD").is_err());
+    }
+}
@@ -1,11 +1,14 @@
 use chrono;
 use serde::Deserialize;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+
+use crate::{aggregation::AggregationStrategy, oracle_source::SourceRole};
 
 /// Application configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
-    /// Solana RPC endpoint
-    pub solana_rpc_url: String,
+    /// Solana RPC endpoints, in order of preference until health-scored
+    pub solana_rpc_urls: Vec<String>,
     
     /// Solana WebSocket endpoint (for real-time updates)
     pub solana_ws_url: String,
@@ -22,6 +25,30 @@ pub struct Config {
     /// Server port
     pub server_port: u16,
     
+    /// Pyth Hermes endpoint (Hermes source disabled when unset)
+    pub hermes_url: Option<String>,
+    
+    /// Exchange venues for the reference price, as (venue name, REST base URL)
+    pub cex_venues: Vec<(String, String)>,
+    
+    /// Exchange markets overriding the default book of a symbol, as
+    /// (venue name, symbol, market); Binance needs one per USD symbol
+    pub cex_markets: Vec<(String, String, String)>,
+    
+    /// Whether the exchange reference price votes or only sanity-checks
+    pub cex_role: SourceRole,
+    
+    /// Switchboard On-Demand pull feeds, as (symbol, feed address); these
+    /// replace the v2 aggregator of the same symbol
+    pub switchboard_pull_feeds: Vec<(String, String)>,
+    
+    /// AMM pools priced as symbols, as (symbol, pool spec; see `amm_client::PoolConfig`)
+    pub amm_pools: Vec<(String, String)>,
+    
+    /// Fee payer for simulating the program's `validate_price_consensus`;
+    /// when set, every consensus price is cross-checked on-chain
+    pub onchain_validation_payer: Option<String>,
+    
     /// Oracle settings
     pub oracle_config: OracleConfig,
 }
@@ -31,18 +58,159 @@ pub struct OracleConfig {
     /// Maximum price age before considered stale (seconds)
     pub max_price_age_seconds: i64,
     
+    /// Maximum slots a price may lag the slot it was read at
+    pub max_price_age_slots: u64,
+    
     /// Maximum confidence interval (basis points)
     pub max_confidence_bps: u64,
     
     /// Maximum price deviation between sources (basis points)
     pub max_deviation_bps: u64,
+    
+    /// Maximum deviation of consensus from a sanity-check reference (basis points)
+    pub max_reference_deviation_bps: u64,
+    
+    /// Decode Pyth publisher components (publisher count and dispersion)
+    pub pyth_publisher_components: bool,
+    
+    /// Prices backed by fewer live publishers are rejected (0 = off;
+    /// enables publisher components)
+    pub min_publishers: u32,
+    
+    /// AMM pools with less quote-side depth are rejected (quote units)
+    pub min_pool_liquidity: u64,
+    
+    /// Trade size whose AMM price impact is used as confidence (quote units)
+    pub pool_probe_size: u64,
+    
+    /// Symbols quoted as signed prices (spreads, basis), which may go negative
+    pub signed_price_symbols: Vec<String>,
+    
+    /// Timeout for a single Solana RPC request (milliseconds)
+    pub rpc_timeout_ms: u64,
+    
+    /// Calls per source price fetch, including the first (1 = no retries)
+    pub source_max_attempts: u32,
+    
+    /// Backoff ceiling before the first retry, doubled per retry (milliseconds)
+    pub retry_base_delay_ms: u64,
+    
+    /// Largest backoff between retries (milliseconds)
+    pub retry_max_delay_ms: u64,
+    
+    /// Consecutive failures after which a source is skipped
+    pub breaker_failure_threshold: u32,
+    
+    /// How long a tripped source is skipped before a trial call (milliseconds)
+    pub breaker_cooldown_ms: u64,
+    
+    /// Deadline for one source's price in a consensus request (milliseconds);
+    /// late sources are left out
+    pub source_deadline_ms: u64,
+    
+    /// Voting sources that must survive validation for a consensus price,
+    /// capped at the voters configured for the symbol
+    pub min_voting_sources: usize,
+    
+    /// Commitment level of oracle account reads and subscriptions
+    pub commitment: CommitmentPolicy,
+    
+    /// How source prices are combined, unless overridden for the symbol
+    pub aggregation: AggregationStrategy,
+    
+    /// Aggregation strategy overrides, as (symbol, strategy)
+    pub symbol_aggregation: Vec<(String, AggregationStrategy)>,
+}
+
+/// Commitment of oracle account reads
+///
+/// A symbol override beats a source override, which beats the default
+/// (`confirmed` unless configured).
+///
+/// # Example
+/// ```rust
+/// let policy = CommitmentPolicy {
+///     default: CommitmentLevel::Confirmed,
+///     sources: vec![("switchboard".to_string(), CommitmentLevel::Finalized)],
+///     symbols: vec![("BTC/USD".to_string(), CommitmentLevel::Processed)],
+/// };
+/// assert_eq!(policy.resolve("Switchboard", "ETH/USD"), CommitmentConfig::finalized());
+/// assert_eq!(policy.resolve("Switchboard", "BTC/USD"), CommitmentConfig::processed());
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitmentPolicy {
+    /// Commitment of every read without an override
+    pub default: CommitmentLevel,
+    
+    /// Overrides by source name (`OracleSource::name`, case-insensitive)
+    pub sources: Vec<(String, CommitmentLevel)>,
+    
+    /// Overrides by symbol
+    pub symbols: Vec<(String, CommitmentLevel)>,
+}
+
+impl Default for CommitmentPolicy {
+    fn default() -> Self {
+        Self {
+            default: CommitmentLevel::Confirmed,
+            sources: Vec::new(),
+            symbols: Vec::new(),
+        }
+    }
+}
+
+impl CommitmentPolicy {
+    /// Commitment for reading `symbol` from the source named `source`
+    pub fn resolve(&self, source: &str, symbol: &str) -> CommitmentConfig {
+        let commitment = self.symbols.iter()
+            .find(|(s, _)| s == symbol)
+            .or_else(|| self.sources.iter().find(|(s, _)| s.eq_ignore_ascii_case(source)))
+            .map_or(self.default, |(_, level)| *level);
+
+        CommitmentConfig { commitment }
+    }
+}
+
+/// Parse `processed`, `confirmed` or `finalized`
+fn parse_commitment(level: &str) -> anyhow::Result<CommitmentLevel> {
+    match level.trim().to_lowercase().as_str() {
+        "processed" => Ok(CommitmentLevel::Processed),
+        "confirmed" => Ok(CommitmentLevel::Confirmed),
+        "finalized" => Ok(CommitmentLevel::Finalized),
+        _ => Err(anyhow::anyhow!("Unknown commitment level {:?}", level)),
+    }
+}
+
+/// Parse a comma-separated `name=level` list of commitment overrides
+fn parse_commitment_overrides(var: &str) -> anyhow::Result<Vec<(String, CommitmentLevel)>> {
+    std::env::var(var)
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|entry| {
+            let (name, level) = entry.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("{} entry {:?} is not name=level", var, entry))?;
+            Ok((name.trim().to_string(), parse_commitment(level)?))
+        })
+        .collect()
 }
 
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> anyhow::Result<Self> {
-        Ok(Config {
-            solana_rpc_url: std::env::var("SOLANA_RPC_URL")?,
+        let rpc_timeout_ms: u64 = std::env::var("RPC_TIMEOUT_MS")
+            .unwrap_or_else(|_| "5000".to_string())
+            .parse()?;
+
+        let config = Config {
+            solana_rpc_urls: match std::env::var("SOLANA_RPC_URLS") {
+                Ok(urls) if !urls.trim().is_empty() => urls
+                    .split(',')
+                    .map(|url| url.trim().to_string())
+                    .filter(|url| !url.is_empty())
+                    .collect(),
+                _ => vec![std::env::var("SOLANA_RPC_URL")?],
+            },
             solana_ws_url: std::env::var("SOLANA_WS_URL")?,
             database_url: std::env::var("DATABASE_URL")?,
             redis_url: std::env::var("REDIS_URL")
@@ -52,17 +220,149 @@ impl Config {
             server_port: std::env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()?,
+            hermes_url: std::env::var("HERMES_URL").ok().filter(|url| !url.is_empty()),
+            cex_venues: std::env::var("CEX_VENUES")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|venue| venue.split_once('=')
+                    .map(|(name, url)| (name.trim().to_string(), url.trim().to_string()))
+                    .ok_or_else(|| anyhow::anyhow!("CEX_VENUES entry {:?} is not name=url", venue)))
+                .collect::<anyhow::Result<_>>()?,
+            cex_markets: std::env::var("CEX_MARKETS")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|entry| entry.split_once('=')
+                    .and_then(|(key, market)| key.split_once(':').map(|(venue, symbol)| (
+                        venue.trim().to_string(), symbol.trim().to_string(), market.trim().to_string(),
+                    )))
+                    .ok_or_else(|| anyhow::anyhow!("CEX_MARKETS entry {:?} is not venue:symbol=market", entry)))
+                .collect::<anyhow::Result<_>>()?,
+            switchboard_pull_feeds: std::env::var("SWITCHBOARD_PULL_FEEDS")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|feed| feed.split_once('=')
+                    .map(|(symbol, address)| (symbol.trim().to_string(), address.trim().to_string()))
+                    .ok_or_else(|| anyhow::anyhow!("SWITCHBOARD_PULL_FEEDS entry {:?} is not symbol=address", feed)))
+                .collect::<anyhow::Result<_>>()?,
+            amm_pools: std::env::var("AMM_POOLS")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|pool| pool.split_once('=')
+                    .map(|(symbol, spec)| (symbol.trim().to_string(), spec.trim().to_string()))
+                    .ok_or_else(|| anyhow::anyhow!("AMM_POOLS entry {:?} is not symbol=spec", pool)))
+                .collect::<anyhow::Result<_>>()?,
+            onchain_validation_payer: std::env::var("ONCHAIN_VALIDATION_PAYER").ok().filter(|payer| !payer.is_empty()),
+            cex_role: std::env::var("CEX_ROLE")
+                .unwrap_or_else(|_| "sanity_check".to_string())
+                .parse()?,
             oracle_config: OracleConfig {
                 max_price_age_seconds: std::env::var("MAX_PRICE_AGE_SECONDS")
                     .unwrap_or_else(|_| "30".to_string())
                     .parse()?,
+                max_price_age_slots: std::env::var("MAX_PRICE_AGE_SLOTS")
+                    .unwrap_or_else(|_| "75".to_string())
+                    .parse()?,
                 max_confidence_bps: std::env::var("MAX_CONFIDENCE_BPS")
                     .unwrap_or_else(|_| "100".to_string())
                     .parse()?,
                 max_deviation_bps: std::env::var("MAX_DEVIATION_BPS")
                     .unwrap_or_else(|_| "100".to_string())
                     .parse()?,
+                max_reference_deviation_bps: std::env::var("MAX_REFERENCE_DEVIATION_BPS")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()?,
+                pyth_publisher_components: std::env::var("PYTH_PUBLISHER_COMPONENTS")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()?,
+                min_publishers: std::env::var("MIN_PUBLISHERS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()?,
+                min_pool_liquidity: std::env::var("MIN_POOL_LIQUIDITY")
+                    .unwrap_or_else(|_| "50000".to_string())
+                    .parse()?,
+                pool_probe_size: std::env::var("POOL_PROBE_SIZE")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()?,
+                signed_price_symbols: std::env::var("SIGNED_PRICE_SYMBOLS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                rpc_timeout_ms,
+                source_max_attempts: std::env::var("SOURCE_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()?,
+                retry_base_delay_ms: std::env::var("RETRY_BASE_DELAY_MS")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                retry_max_delay_ms: std::env::var("RETRY_MAX_DELAY_MS")
+                    .unwrap_or_else(|_| "2000".to_string())
+                    .parse()?,
+                breaker_failure_threshold: std::env::var("BREAKER_FAILURE_THRESHOLD")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                breaker_cooldown_ms: std::env::var("BREAKER_COOLDOWN_MS")
+                    .unwrap_or_else(|_| "30000".to_string())
+                    .parse()?,
+                // Room for one timed-out RPC request plus a retry
+                source_deadline_ms: match std::env::var("SOURCE_DEADLINE_MS") {
+                    Ok(ms) => ms.parse()?,
+                    Err(_) => rpc_timeout_ms.saturating_mul(2),
+                },
+                min_voting_sources: std::env::var("MIN_VOTING_SOURCES")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()?,
+                commitment: CommitmentPolicy {
+                    default: parse_commitment(
+                        &std::env::var("RPC_COMMITMENT").unwrap_or_else(|_| "confirmed".to_string())
+                    )?,
+                    sources: parse_commitment_overrides("SOURCE_COMMITMENTS")?,
+                    symbols: parse_commitment_overrides("SYMBOL_COMMITMENTS")?,
+                },
+                aggregation: std::env::var("AGGREGATION_STRATEGY")
+                    .unwrap_or_else(|_| "median".to_string())
+                    .parse()?,
+                symbol_aggregation: std::env::var("SYMBOL_AGGREGATION")
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|s| !s.trim().is_empty())
+                    .map(|entry| {
+                        let (symbol, strategy) = entry.split_once('=')
+                            .ok_or_else(|| anyhow::anyhow!("SYMBOL_AGGREGATION entry {:?} is not symbol=strategy", entry))?;
+                        Ok((symbol.trim().to_string(), strategy.parse()?))
+                    })
+                    .collect::<anyhow::Result<_>>()?,
             },
-        })
+        };
+
+        config.oracle_config.validate()?;
+        Ok(config)
+    }
+}
+
+impl OracleConfig {
+    /// Reject settings that contradict each other
+    ///
+    /// # Errors
+    /// When the source deadline is shorter than one RPC request (a slow
+    /// RPC would then never time out on its own, only be cancelled), or the
+    /// voting quorum is zero.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.source_deadline_ms < self.rpc_timeout_ms {
+            return Err(anyhow::anyhow!(
+                "SOURCE_DEADLINE_MS ({}) must be at least RPC_TIMEOUT_MS ({})",
+                self.source_deadline_ms,
+                self.rpc_timeout_ms,
+            ));
+        }
+        if self.min_voting_sources == 0 {
+            return Err(anyhow::anyhow!("MIN_VOTING_SOURCES must be at least 1"));
+        }
+        Ok(())
     }
 }
\ No newline at end of file
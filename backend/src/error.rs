@@ -1,4 +1,5 @@
 
+use oracle_consensus::ConsensusError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -18,14 +19,52 @@ pub enum OracleError {
     #[error("Solana RPC error: {0}")]
     SolanaError(String),
     
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
     
     #[error("Redis error: {0}")]
     RedisError(#[from] redis::RedisError),
     
+    #[error("Invalid price: {0}")]
+    InvalidPrice(String),
+    
+    #[error("Pyth feed is not trading: {0}")]
+    PythNotTrading(String),
+    
+    #[error("Oracle round below quorum: {0}")]
+    InsufficientOracleQuorum(String),
+    
+    #[error("Too few price sources: {0}")]
+    InsufficientSources(String),
+    
+    #[error("Insufficient liquidity: {0}")]
+    LowLiquidity(String),
+    
+    #[error("Source unavailable: {0}")]
+    SourceUnavailable(String),
+    
     #[error("Parse error: {0}")]
     ParseError(String),
 }
 
-pub type Result<T> = std::result::Result<T, OracleError>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, OracleError>;
+
+impl From<ConsensusError> for OracleError {
+    fn from(error: ConsensusError) -> Self {
+        match error {
+            ConsensusError::NoPriceData => OracleError::NoPriceData(error.to_string()),
+            ConsensusError::StalePriceData => OracleError::StalePrice(error.to_string()),
+            ConsensusError::ConfidenceTooLarge => OracleError::HighConfidence(error.to_string()),
+            ConsensusError::PriceDeviationTooLarge => OracleError::PriceDeviation(error.to_string()),
+            ConsensusError::ZeroPrice | ConsensusError::NegativePrice => {
+                OracleError::InvalidPrice(error.to_string())
+            }
+            ConsensusError::ExponentMismatch | ConsensusError::MathOverflow => {
+                OracleError::ParseError(error.to_string())
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,499 @@
+//! Pyth Hermes Client
+//!
+//! Reads Pyth prices from the Hermes HTTP API instead of Solana accounts, so
+//! the service keeps a Pyth price path when Solana RPC is down or lagging.
+//! Prices are tagged `PriceSource::Hermes` to keep them apart from on-chain
+//! Pyth reads in consensus and history. Hermes serves the same publisher
+//! data as the Pyth accounts, so it is a `SourceRole::Fallback` for Pyth:
+//! it only votes when the on-chain read fails or is rejected.
+//!
+//! # Endpoints
+//! ```text
+//! GET /v2/updates/price/latest?ids[]=<feed id>&parsed=true   → latest prices
+//! GET /v2/updates/price/stream?ids[]=<feed id>&parsed=true   → SSE, one event per update
+//! ```
+//!
+//! Feed ids are the 32-byte hex Pyth price feed ids (with or without `0x`),
+//! e.g. BTC/USD = `e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43`.
+
+use crate::{
+    error::{OracleError, Result},
+    oracle_source::{OracleSource, SourceRole},
+    subscriptions::{Backoff, LivePrices},
+    types::{scale_by_exponent, FeedStatus, PriceData, PriceSource},
+};
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tracing::{debug, info, warn};
+
+/// Client for the Pyth Hermes API
+#[derive(Clone)]
+pub struct HermesClient {
+    http: reqwest::Client,
+
+    /// Hermes base URL (e.g. "https://hermes.pyth.network")
+    base_url: String,
+
+    /// Feed id (lowercase hex, no `0x`) by symbol
+    feeds: HashMap<String, String>,
+
+    /// Upper bound on a latest-price request
+    timeout: Duration,
+}
+
+impl HermesClient {
+    /// Create a new client
+    ///
+    /// # Arguments
+    /// * `base_url` - Hermes endpoint
+    /// * `timeout` - Upper bound on a single latest-price request
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(|e| OracleError::ParseError(format!("Invalid HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            feeds: HashMap::new(),
+            timeout,
+        })
+    }
+
+    /// Register the Pyth feed id for a symbol
+    pub fn register_feed(&mut self, symbol: String, feed_id: &str) -> Result<()> {
+        let feed_id = normalize_feed_id(feed_id)?;
+
+        debug!("Registered Hermes feed for {}: {}", symbol, feed_id);
+        self.feeds.insert(symbol, feed_id);
+
+        Ok(())
+    }
+
+    /// Fetch the latest prices of `symbols` in one request
+    ///
+    /// # Errors
+    /// * `NoPriceData` - A symbol has no registered feed, or Hermes didn't return it
+    /// * `SolanaError` - The request failed (Hermes is the Pyth network API)
+    /// * `Timeout` - Hermes didn't answer in time
+    pub async fn get_latest(&self, symbols: &[String]) -> Result<Vec<PriceData>> {
+        let ids = symbols.iter()
+            .map(|symbol| self.feed_id(symbol))
+            .collect::<Result<Vec<_>>>()?;
+
+        let response = self.http
+            .get(format!("{}/v2/updates/price/latest", self.base_url))
+            .query(&feed_query(&ids))
+            .timeout(self.timeout)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(request_error)?
+            .json::<HermesResponse>()
+            .await
+            .map_err(|e| OracleError::ParseError(format!("Invalid Hermes response: {}", e)))?;
+
+        let by_id: HashMap<String, HermesPriceUpdate> = response.parsed
+            .into_iter()
+            .map(|update| (update.id.to_lowercase(), update))
+            .collect();
+
+        symbols.iter()
+            .zip(&ids)
+            .map(|(symbol, id)| {
+                by_id.get(id)
+                    .ok_or_else(|| OracleError::NoPriceData(format!("Hermes returned no price for {}", symbol)))
+                    .and_then(|update| update.to_price_data(symbol))
+            })
+            .collect()
+    }
+
+    /// Open an SSE stream of price updates for `symbols`
+    ///
+    /// Yields one `PriceData` per feed per update, with the Pythnet slot of
+    /// the update. The stream ends when Hermes closes the connection.
+    ///
+    /// # Example
+    /// ```rust
+    /// let mut updates = hermes.stream_prices(&["BTC/USD".to_string()]).await?;
+    /// while let Some(update) = updates.next().await {
+    ///     let (price, slot) = update?;
+    ///     println!("{} @ {}: ${}", price.symbol, slot, price.price);
+    /// }
+    /// ```
+    pub async fn stream_prices(
+        &self,
+        symbols: &[String],
+    ) -> Result<impl Stream<Item = Result<(PriceData, u64)>>> {
+        let ids = symbols.iter()
+            .map(|symbol| self.feed_id(symbol))
+            .collect::<Result<Vec<_>>>()?;
+        let symbols_by_id: HashMap<String, String> = ids.iter().cloned().zip(symbols.iter().cloned()).collect();
+
+        let response = self.http
+            .get(format!("{}/v2/updates/price/stream", self.base_url))
+            .query(&feed_query(&ids))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(request_error)?;
+
+        let events = response.bytes_stream()
+            .scan(SseParser::default(), |parser, chunk| {
+                let events: Vec<Result<String>> = match chunk {
+                    Ok(bytes) => parser.push(&bytes).into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(request_error(e))],
+                };
+                futures::future::ready(Some(stream::iter(events)))
+            })
+            .flatten();
+
+        Ok(events.flat_map(move |event| {
+            let updates: Vec<Result<(PriceData, u64)>> = match event.and_then(|data| parse_event(&data)) {
+                Ok(parsed) => parsed.into_iter()
+                    .filter_map(|update| {
+                        let symbol = symbols_by_id.get(&update.id.to_lowercase())?;
+                        let slot = update.metadata.as_ref().map_or(0, |m| m.slot);
+                        Some(update.to_price_data(symbol).map(|price| (price, slot)))
+                    })
+                    .collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(updates)
+        }))
+    }
+
+    /// Keep an SSE stream open and push every update into `live`
+    ///
+    /// Reconnects with backoff whenever the stream ends or fails. On
+    /// disconnect the Hermes entries stay until replaced; the aggregator's
+    /// staleness check rejects them if the stream stays down.
+    pub async fn stream_into(self, live: Arc<LivePrices>) {
+        let symbols: Vec<String> = self.feeds.keys().cloned().collect();
+        if symbols.is_empty() {
+            warn!("No Hermes feeds to stream");
+            return;
+        }
+
+        let mut backoff = Backoff::new();
+        loop {
+            match self.stream_prices(&symbols).await {
+                Ok(updates) => {
+                    info!("Streaming {} Hermes feeds", symbols.len());
+                    backoff.reset();
+
+                    let mut updates = std::pin::pin!(updates);
+                    while let Some(update) = updates.next().await {
+                        match update {
+                            Ok((price, slot)) => live.update(price, slot),
+                            Err(e) => {
+                                warn!("Hermes stream error: {}", e);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => warn!("Hermes stream failed: {}", e),
+            }
+
+            let delay = backoff.next_delay();
+            info!("Reconnecting to Hermes in {:?}", delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn feed_id(&self, symbol: &str) -> Result<String> {
+        self.feeds.get(symbol).cloned().ok_or_else(|| OracleError::NoPriceData(
+            format!("No Hermes feed registered for {}", symbol)
+        ))
+    }
+}
+
+#[async_trait]
+impl OracleSource for HermesClient {
+    fn name(&self) -> &'static str {
+        "Hermes"
+    }
+
+    fn source(&self) -> PriceSource {
+        PriceSource::Hermes
+    }
+
+    fn role(&self) -> SourceRole {
+        SourceRole::Fallback(PriceSource::Pyth)
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.feeds.keys().cloned().collect()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        let mut prices = self.get_latest(&[symbol.to_string()]).await?;
+        prices.pop().ok_or_else(|| OracleError::NoPriceData(
+            format!("Hermes returned no price for {}", symbol)
+        ))
+    }
+
+    /// One request for all symbols; if it fails, every symbol gets the error
+    async fn get_prices(&self, symbols: &[String]) -> Vec<Result<PriceData>> {
+        match self.get_latest(symbols).await {
+            Ok(prices) => prices.into_iter().map(Ok).collect(),
+            Err(e) => symbols.iter().map(|_| Err(OracleError::NoPriceData(e.to_string()))).collect(),
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        let Some(symbol) = self.feeds.keys().next() else {
+            warn!("No Hermes feeds registered for health check");
+            return false;
+        };
+
+        match self.get_price(symbol).await {
+            Ok(_) => {
+                debug!("Hermes health check passed");
+                true
+            }
+            Err(e) => {
+                warn!("Hermes health check failed: {}", e);
+                false
+            }
+        }
+    }
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+/// Body of `/v2/updates/price/latest` and of each SSE event
+#[derive(Debug, Deserialize)]
+struct HermesResponse {
+    #[serde(default)]
+    parsed: Vec<HermesPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPriceUpdate {
+    /// Feed id, hex without `0x`
+    id: String,
+    price: HermesPrice,
+    metadata: Option<HermesMetadata>,
+}
+
+/// Hermes encodes 64-bit integers as strings
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesMetadata {
+    /// Pythnet slot of the update
+    slot: u64,
+}
+
+impl HermesPriceUpdate {
+    fn to_price_data(&self, symbol: &str) -> Result<PriceData> {
+        let parse = |field: &str, value: &str| -> Result<i128> {
+            value.parse().map_err(|e| OracleError::ParseError(format!("Invalid Hermes {} {:?}: {}", field, value, e)))
+        };
+        // The exponent comes straight off the network
+        let scale = |value: i128| scale_by_exponent(value, self.price.expo);
+
+        Ok(PriceData {
+            symbol: symbol.to_string(),
+            price: scale(parse("price", &self.price.price)?)?,
+            confidence: scale(parse("conf", &self.price.conf)?)?,
+            timestamp: self.price.publish_time,
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Hermes,
+        })
+    }
+}
+
+fn parse_event(data: &str) -> Result<Vec<HermesPriceUpdate>> {
+    serde_json::from_str::<HermesResponse>(data)
+        .map(|response| response.parsed)
+        .map_err(|e| OracleError::ParseError(format!("Invalid Hermes event: {}", e)))
+}
+
+fn feed_query(ids: &[String]) -> Vec<(&'static str, &str)> {
+    let mut query: Vec<_> = ids.iter().map(|id| ("ids[]", id.as_str())).collect();
+    query.push(("parsed", "true"));
+    query
+}
+
+fn normalize_feed_id(feed_id: &str) -> Result<String> {
+    let id = feed_id.trim_start_matches("0x").to_lowercase();
+    if id.len() != 64 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(OracleError::ParseError(format!("Invalid Pyth feed id: {}", feed_id)));
+    }
+
+    Ok(id)
+}
+
+fn request_error(e: reqwest::Error) -> OracleError {
+    if e.is_timeout() {
+        OracleError::Timeout(format!("Hermes request timed out: {}", e))
+    } else {
+        OracleError::SolanaError(format!("Hermes request failed: {}", e))
+    }
+}
+
+/// Incremental Server-Sent Events parser
+///
+/// Chunks can split events anywhere; complete events (terminated by a blank
+/// line) are returned as their joined `data:` lines.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: String,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.push_str(&String::from_utf8_lossy(chunk).replace("\r\n", "\n"));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let event: String = self.buffer.drain(..end + 2).collect();
+            let data: Vec<&str> = event.lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::RawQuery, routing::get, Router};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    const BTC_FEED: &str = "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+    const ETH_FEED: &str = "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace";
+
+    fn update(id: &str, price: &str, slot: u64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "price": { "price": price, "conf": "2500000000", "expo": -8, "publish_time": 1_702_234_567 },
+            "ema_price": { "price": price, "conf": "2500000000", "expo": -8, "publish_time": 1_702_234_567 },
+            "metadata": { "slot": slot, "proof_available_time": 1_702_234_568, "prev_publish_time": 1_702_234_566 },
+        })
+    }
+
+    /// Local Hermes serving fixed BTC and ETH prices
+    async fn mock_hermes() -> String {
+        async fn latest(RawQuery(query): RawQuery) -> axum::Json<serde_json::Value> {
+            let query = query.unwrap_or_default();
+            let mut parsed = Vec::new();
+            if query.contains(BTC_FEED) {
+                parsed.push(update(BTC_FEED, "5000012345678", 100));
+            }
+            if query.contains(ETH_FEED) {
+                parsed.push(update(ETH_FEED, "300000000000", 100));
+            }
+            axum::Json(json!({ "binary": { "encoding": "hex", "data": [] }, "parsed": parsed }))
+        }
+
+        async fn stream() -> ([(axum::http::HeaderName, &'static str); 1], String) {
+            let events = [
+                json!({ "parsed": [update(BTC_FEED, "5000000000000", 101)] }),
+                json!({ "parsed": [update(BTC_FEED, "5001000000000", 102), update(ETH_FEED, "300100000000", 102)] }),
+            ];
+            let body = events.iter().map(|event| format!("data:{}\n\n", event)).collect();
+            ([(axum::http::header::CONTENT_TYPE, "text/event-stream")], body)
+        }
+
+        let app = Router::new()
+            .route("/v2/updates/price/latest", get(latest))
+            .route("/v2/updates/price/stream", get(stream));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        url
+    }
+
+    async fn client() -> HermesClient {
+        let mut client = HermesClient::new(&mock_hermes().await, Duration::from_secs(5)).unwrap();
+        client.register_feed("BTC/USD".to_string(), &format!("0x{}", BTC_FEED.to_uppercase())).unwrap();
+        client.register_feed("ETH/USD".to_string(), ETH_FEED).unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_latest_price() {
+        let client = client().await;
+
+        let price = client.get_price("BTC/USD").await.unwrap();
+        assert_eq!(price.price.to_string(), "50000.12345678");
+        assert_eq!(price.confidence, Decimal::from(25));
+        assert_eq!(price.timestamp, 1_702_234_567);
+        assert_eq!(price.source, PriceSource::Hermes);
+
+        let prices = client.get_prices(&["ETH/USD".to_string(), "BTC/USD".to_string()]).await;
+        assert_eq!(prices[0].as_ref().unwrap().price, Decimal::from(3_000));
+        assert_eq!(prices[1].as_ref().unwrap().symbol, "BTC/USD");
+
+        assert!(matches!(client.get_price("SOL/USD").await, Err(OracleError::NoPriceData(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stream_prices() {
+        let client = client().await;
+        let symbols = vec!["BTC/USD".to_string(), "ETH/USD".to_string()];
+
+        let updates: Vec<_> = client.stream_prices(&symbols).await.unwrap()
+            .map(|update| update.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[0].0.price, Decimal::from(50_000));
+        assert_eq!(updates[0].1, 101);
+        assert_eq!(updates[2].0.symbol, "ETH/USD");
+        assert_eq!(updates[2].0.price, Decimal::from(3_001));
+    }
+
+    #[test]
+    fn test_out_of_range_exponent() {
+        for expo in [19, i32::MAX, i32::MIN] {
+            let mut event = update(BTC_FEED, "5000012345678", 100);
+            event["price"]["expo"] = json!(expo);
+            let update: HermesPriceUpdate = serde_json::from_value(event).unwrap();
+
+            assert!(matches!(update.to_price_data("BTC/USD"), Err(OracleError::ParseError(_))), "expo {}", expo);
+        }
+    }
+
+    #[test]
+    fn test_sse_parser_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b": keep-alive\n\ndata: {\"a\"").is_empty());
+        assert_eq!(parser.push(b":1}\r\n\r\ndata:x\n"), vec!["{\"a\":1}".to_string()]);
+        assert_eq!(parser.push(b"\n"), vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn test_normalize_feed_id() {
+        assert_eq!(normalize_feed_id(&format!("0x{}", BTC_FEED.to_uppercase())).unwrap(), BTC_FEED);
+        assert!(normalize_feed_id("0x1234").is_err());
+    }
+}
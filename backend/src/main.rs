@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -11,12 +11,31 @@ mod price_aggregator;
 mod cache;
 mod database;
 mod api;
+mod onchain;
+mod account_batch;
+mod subscriptions;
+mod oracle_source;
+mod hermes_client;
+mod cex_client;
+mod amm_client;
+mod rpc_pool;
+mod resilience;
+mod aggregation;
 
 use config::Config;
 use price_aggregator::PriceAggregator;
 use cache::CachedPriceFetcher;
 use database::Database;
 use api::{AppState, create_router};
+use subscriptions::SubscriptionManager;
+use pyth_client::PythClient;
+use switchboard_client::SwitchboardClient;
+use hermes_client::HermesClient;
+use cex_client::CexClient;
+use amm_client::AmmClient;
+use rpc_pool::RpcPool;
+use resilience::Resilient;
+use onchain::OnChainValidator;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -44,38 +63,112 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("💾 Connecting to Redis cache...");
     let cache = CachedPriceFetcher::new(&config.redis_url).await?;
 
-    // Initialize price aggregator
-    tracing::info!("🔗 Initializing oracle aggregator...");
-    let mut aggregator = PriceAggregator::new(
-        &config.solana_rpc_url,
-        config.oracle_config.clone(),
-    );
+    // Initialize oracle sources
+    tracing::info!("🔗 Initializing oracle sources...");
+    let rpc_timeout = Duration::from_millis(config.oracle_config.rpc_timeout_ms);
+    let rpc_pool = Arc::new(RpcPool::new(&config.solana_rpc_urls, rpc_timeout)?);
+    tracing::info!("🩺 Health-checking {} RPC endpoints...", config.solana_rpc_urls.len());
+    tokio::spawn(rpc_pool.clone().run_health_checks(Duration::from_secs(10)));
+
+    let mut pyth = PythClient::with_pool(rpc_pool.clone(), rpc_timeout);
+    if config.oracle_config.pyth_publisher_components || config.oracle_config.min_publishers > 0 {
+        pyth.enable_publisher_components();
+    }
+    pyth.set_commitment(config.oracle_config.commitment.clone());
+    let mut switchboard = SwitchboardClient::with_pool(rpc_pool.clone(), rpc_timeout);
+    switchboard.set_commitment(config.oracle_config.commitment.clone());
 
     // Register trading symbols
     // TODO: Load from config file or database
-    aggregator.register_symbol(
-        "BTC/USD",
-        "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU", // Pyth BTC/USD (devnet)
-        "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee", // Switchboard BTC/USD (devnet)
-    )?;
-
-    aggregator.register_symbol(
-        "ETH/USD",
-        "JBu1AL4obBcCMqKBBxhpWCNUt136ijcuMZLFvTP7iWdB", // Pyth ETH/USD (devnet)
-        "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR", // Switchboard ETH/USD (devnet)
-    )?;
-
-    aggregator.register_symbol(
-        "SOL/USD",
-        "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix", // Pyth SOL/USD (devnet)
-        "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR", // Switchboard SOL/USD (devnet)
-    )?;
+    pyth.register_feed("BTC/USD".to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU")?; // devnet
+    pyth.register_feed("ETH/USD".to_string(), "JBu1AL4obBcCMqKBBxhpWCNUt136ijcuMZLFvTP7iWdB")?; // devnet
+    pyth.register_feed("SOL/USD".to_string(), "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix")?; // devnet
+
+    switchboard.register_aggregator("BTC/USD".to_string(), "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee")?; // devnet
+    switchboard.register_aggregator("ETH/USD".to_string(), "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR")?; // devnet
+    switchboard.register_aggregator("SOL/USD".to_string(), "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR")?; // devnet
+    for (symbol, address) in &config.switchboard_pull_feeds {
+        switchboard.register_pull_feed(symbol.clone(), address)?;
+    }
+
+    // Initialize price aggregator
+    tracing::info!("🧮 Initializing price aggregator...");
+    let mut aggregator = PriceAggregator::with_pool(
+        rpc_pool.clone(),
+        config.oracle_config.clone(),
+    );
+    if let Some(payer) = &config.onchain_validation_payer {
+        tracing::info!("⛓️  Cross-checking consensus with the on-chain program...");
+        aggregator.set_onchain_validator(OnChainValidator::new(&config.solana_rpc_urls[0], payer, rpc_timeout)?);
+    }
+    // Every source retries transient errors and is skipped while its breaker is open
+    aggregator.add_source(Resilient::from_config(pyth, &config.oracle_config));
+    // Feed details for /api/v1/oracles/switchboard; shares the RPC pool
+    let switchboard_info = Arc::new(switchboard.clone());
+    aggregator.add_source(Resilient::from_config(switchboard, &config.oracle_config));
+
+    // Pyth over Hermes stands in for on-chain Pyth when Solana RPC is unavailable
+    if let Some(hermes_url) = &config.hermes_url {
+        let mut hermes = HermesClient::new(hermes_url, rpc_timeout)?;
+        hermes.register_feed("BTC/USD".to_string(), "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43")?;
+        hermes.register_feed("ETH/USD".to_string(), "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace")?;
+        hermes.register_feed("SOL/USD".to_string(), "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d")?;
+
+        tracing::info!("📡 Streaming Pyth prices from Hermes...");
+        tokio::spawn(hermes.clone().stream_into(aggregator.live_prices()));
+        aggregator.add_source(Resilient::from_config(hermes, &config.oracle_config));
+    }
+
+    // AMM pools for tokens without an oracle feed
+    if !config.amm_pools.is_empty() {
+        let mut amm = AmmClient::with_pool(
+            rpc_pool.clone(),
+            config.oracle_config.min_pool_liquidity.into(),
+            config.oracle_config.pool_probe_size.into(),
+            rpc_timeout,
+        );
+        amm.set_commitment(config.oracle_config.commitment.clone());
+        for (symbol, spec) in &config.amm_pools {
+            amm.register_pool(symbol.clone(), spec.parse()?);
+        }
+
+        tracing::info!("💧 Pricing {} symbols from AMM pools", config.amm_pools.len());
+        aggregator.add_source(Resilient::from_config(amm, &config.oracle_config));
+    }
+
+    // Exchange order books as a manipulation check (or extra voter)
+    if !config.cex_venues.is_empty() {
+        let mut cex = CexClient::new(config.cex_role, rpc_timeout)?;
+        for (venue, url) in &config.cex_venues {
+            cex.add_venue(venue, url)?;
+        }
+        for (venue, symbol, market) in &config.cex_markets {
+            cex.add_market(venue, symbol, market)?;
+        }
+        for symbol in aggregator.symbols() {
+            cex.register_symbol(symbol);
+        }
+
+        tracing::info!("🏦 Using {} exchange venues as {:?}", config.cex_venues.len(), config.cex_role);
+        aggregator.add_source(Resilient::from_config(cex, &config.oracle_config));
+    }
+
+    // Push price updates over WebSocket
+    tracing::info!("📡 Subscribing to oracle accounts...");
+    let subscriptions = SubscriptionManager::new(
+        &config.solana_ws_url,
+        aggregator.subscription_targets(),
+        aggregator.live_prices(),
+    );
+    tokio::spawn(subscriptions.run());
 
     // Create shared application state
     let state = AppState {
         aggregator: Arc::new(Mutex::new(aggregator)),
         cache: Arc::new(Mutex::new(cache)),
         db: Arc::new(db),
+        switchboard: switchboard_info,
+        rpc_pool,
     };
 
     // Create API router
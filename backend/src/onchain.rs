@@ -0,0 +1,170 @@
+//! On-chain Validation
+//!
+//! Runs the deployed `oracle_price_feed` program's `validate_price_consensus`
+//! through `simulateTransaction`, so the backend can ask the program itself
+//! whether it would accept a set of prices — without paying for or landing
+//! a transaction.
+//!
+//! # Flow
+//! ```text
+//! Backend PriceData (Decimal) → program PriceData (i64 × 10^-8)
+//!         ↓
+//! validate_price_consensus ix → simulateTransaction → return data → PriceData
+//! ```
+
+use crate::{
+    error::{OracleError, Result},
+    types::{PriceData, PriceSource},
+};
+use oracle_price_feed_client as program;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::{str::FromStr, time::Duration};
+use tracing::debug;
+
+/// Simulates program instructions against an RPC node
+pub struct OnChainValidator {
+    rpc_client: RpcClient,
+
+    /// Fee payer for simulated transactions (never signs)
+    payer: Pubkey,
+
+    /// Upper bound on a single simulation request
+    timeout: Duration,
+}
+
+impl OnChainValidator {
+    /// Create a new validator
+    ///
+    /// # Arguments
+    /// * `rpc_url` - Solana RPC endpoint
+    /// * `payer` - Any funded account; simulation skips signature checks
+    /// * `timeout` - Upper bound on a single simulation request
+    pub fn new(rpc_url: &str, payer: &str, timeout: Duration) -> Result<Self> {
+        let payer = Pubkey::from_str(payer)
+            .map_err(|e| OracleError::ParseError(format!("Invalid pubkey: {}", e)))?;
+
+        Ok(Self {
+            rpc_client: RpcClient::new_with_timeout(rpc_url.to_string(), timeout),
+            payer,
+            timeout,
+        })
+    }
+
+    /// Validate prices with the on-chain program and return its consensus
+    ///
+    /// # Errors
+    /// * `InvalidPrice` - The program rejected the prices
+    /// * `SolanaError` - The simulation request failed
+    /// * `Timeout` - The RPC node did not answer within `timeout`
+    /// * `ParseError` - Missing or undecodable return data
+    pub async fn validate_consensus(&self, symbol: &str, prices: &[PriceData]) -> Result<PriceData> {
+        let program_prices = prices
+            .iter()
+            .map(to_program_price)
+            .collect::<Result<Vec<_>>>()?;
+
+        let ix = program::instructions::validate_price_consensus(symbol, program_prices, false);
+        let ix = Instruction {
+            program_id: Pubkey::new_from_array(ix.program_id.to_bytes()),
+            accounts: ix.accounts.iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::new_from_array(meta.pubkey.to_bytes()),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        };
+
+        let tx = Transaction::new_with_payer(&[ix], Some(&self.payer));
+        let simulation = self.rpc_client
+            .simulate_transaction_with_config(&tx, RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                ..RpcSimulateTransactionConfig::default()
+            });
+        let response = tokio::time::timeout(self.timeout, simulation)
+            .await
+            .map_err(|_| OracleError::Timeout(format!("Simulation took longer than {:?}", self.timeout)))?
+            .map_err(|e| OracleError::SolanaError(format!("Simulation failed: {}", e)))?;
+
+        let result = response.value;
+        if let Some(err) = result.err {
+            return Err(OracleError::InvalidPrice(format!(
+                "Program rejected prices for {}: {} (logs: {:?})",
+                symbol, err, result.logs.unwrap_or_default()
+            )));
+        }
+
+        let return_data = result.return_data
+            .ok_or_else(|| OracleError::ParseError("Simulation returned no data".to_string()))?;
+        let program_id = program::PROGRAM_ID.to_string();
+        if return_data.program_id != program_id {
+            return Err(OracleError::ParseError(format!(
+                "Return data from unexpected program {}", return_data.program_id
+            )));
+        }
+
+        let bytes = BASE64.decode(&return_data.data.0)
+            .map_err(|e| OracleError::ParseError(format!("Invalid return data: {}", e)))?;
+        let consensus = program::decode::decode_price_data(&bytes)
+            .map_err(|e| OracleError::ParseError(e.to_string()))?;
+
+        debug!("On-chain consensus for {}: {} × 10^{}", symbol, consensus.price, consensus.expo);
+
+        from_program_price(symbol, &consensus)
+    }
+}
+
+/// Convert a backend price into the program's fixed-point format
+fn to_program_price(price: &PriceData) -> Result<program::PriceData> {
+    let source = match price.source {
+        PriceSource::Pyth | PriceSource::Hermes => program::PriceSource::Pyth,
+        PriceSource::Switchboard => program::PriceSource::Switchboard,
+        PriceSource::Cex | PriceSource::Amm | PriceSource::Aggregate => program::PriceSource::Internal,
+    };
+
+    Ok(program::PriceData::from_quote(&price.to_quote()?, source))
+}
+
+/// Convert the program's fixed-point price back into a backend price
+fn from_program_price(symbol: &str, price: &program::PriceData) -> Result<PriceData> {
+    PriceData::from_quote(symbol, &price.to_quote(), PriceSource::Aggregate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeedStatus;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_program_price_roundtrip() {
+        let price = PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from_str("50000.12345678").unwrap(),
+            confidence: Decimal::from_str("25.5").unwrap(),
+            timestamp: 1_702_234_567,
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Pyth,
+        };
+
+        let fixed = to_program_price(&price).unwrap();
+        assert_eq!(fixed.price, 5_000_012_345_678);
+        assert_eq!(fixed.confidence, 2_550_000_000);
+        assert_eq!(fixed.expo, -8);
+
+        let back = from_program_price("BTC/USD", &fixed).unwrap();
+        assert_eq!(back.price, price.price);
+        assert_eq!(back.confidence, price.confidence);
+        assert_eq!(back.source, PriceSource::Aggregate);
+    }
+}
@@ -0,0 +1,109 @@
+//! Oracle Sources
+//!
+//! The interface every price source implements. `PriceAggregator` only
+//! talks to `dyn OracleSource`, so adding an oracle means implementing this
+//! trait and calling `PriceAggregator::add_source` — the validation and
+//! consensus code stays untouched.
+//!
+//! # Account-based sources
+//! Sources whose prices live in a Solana account (Pyth, Switchboard) also
+//! expose `account_address` and `decode_account`. That opts them into
+//! batched `getMultipleAccounts` snapshots and WebSocket subscriptions.
+//! Off-chain sources keep the defaults and are always read via `get_price`.
+//!
+//! # Roles
+//! A source either votes in the median (`SourceRole::Voter`, the default) or
+//! only sanity-checks the result (`SourceRole::SanityCheck`): consensus is
+//! computed without it and rejected if it strays too far from its price.
+//! A second path to another source's data (Pyth over Hermes) is a
+//! `SourceRole::Fallback`: it votes only when that source has no valid
+//! price, so the same publishers are never counted twice.
+
+use crate::{
+    error::{OracleError, Result},
+    types::{PriceData, PriceSource},
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// How the aggregator uses a source's prices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceRole {
+    /// Price counts towards the consensus median
+    #[default]
+    Voter,
+
+    /// Price is only compared against the consensus
+    SanityCheck,
+
+    /// Price votes in place of the given source, only when that source
+    /// has no valid price
+    Fallback(PriceSource),
+}
+
+impl FromStr for SourceRole {
+    type Err = OracleError;
+
+    fn from_str(role: &str) -> Result<Self> {
+        match role.to_lowercase().as_str() {
+            "voter" => Ok(SourceRole::Voter),
+            "sanity" | "sanity_check" => Ok(SourceRole::SanityCheck),
+            _ => Err(OracleError::ParseError(format!("Unknown source role: {}", role))),
+        }
+    }
+}
+
+/// A source of prices the aggregator can combine
+#[async_trait]
+pub trait OracleSource: Send + Sync {
+    /// Human-readable name for logs and errors (e.g. "Pyth")
+    fn name(&self) -> &'static str;
+
+    /// Identifier stamped on this source's prices and health records
+    fn source(&self) -> PriceSource;
+
+    /// Whether this source votes or only sanity-checks consensus
+    fn role(&self) -> SourceRole {
+        SourceRole::Voter
+    }
+
+    /// Symbols this source can price
+    fn symbols(&self) -> Vec<String>;
+
+    /// Whether this source can price `symbol`
+    fn supports(&self, symbol: &str) -> bool {
+        self.symbols().iter().any(|s| s == symbol)
+    }
+
+    /// Fetch the current price of `symbol`
+    async fn get_price(&self, symbol: &str) -> Result<PriceData>;
+
+    /// Fetch several prices; one result per symbol, in order
+    async fn get_prices(&self, symbols: &[String]) -> Vec<Result<PriceData>> {
+        let mut results = Vec::new();
+
+        for symbol in symbols {
+            results.push(self.get_price(symbol).await);
+        }
+
+        results
+    }
+
+    /// Check the source is reachable and serving fresh data
+    async fn health_check(&self) -> bool;
+
+    /// Solana account holding the price of `symbol`, for account-based sources
+    fn account_address(&self, _symbol: &str) -> Option<Pubkey> {
+        None
+    }
+
+    /// Parse the raw data of the account returned by `account_address`
+    fn decode_account(&self, _symbol: &str, _data: &[u8]) -> Result<PriceData> {
+        Err(OracleError::ParseError(format!(
+            "{} prices are not stored in accounts", self.name()
+        )))
+    }
+}
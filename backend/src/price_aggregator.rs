@@ -2,7 +2,8 @@
 //! 
 //! This module combines prices from multiple oracle sources to produce
 //! a single, reliable consensus price. It implements manipulation resistance
-//! through median calculation and outlier detection.
+//! through median calculation (or another per-symbol `AggregationStrategy`)
+//! and outlier detection.
 //!
 //! # Architecture
 //! ```text
@@ -10,40 +11,69 @@
 //!         ↓
 //! Switchboard → $50,100  →  [Aggregator]  →  Consensus: $50,050
 //!         ↓                      ↓
-//! (Future oracle)           Validates, detects outliers
+//! dyn OracleSource          Validates, detects outliers
 //! ```
+//!
+//! Sources are held as `dyn OracleSource`; adding an oracle never touches
+//! the validation and consensus code below.
+//!
+//! Sanity-check sources (e.g. exchange order books) don't vote: consensus is
+//! computed from the voters, then rejected if a reference price deviates
+//! from it by more than `max_reference_deviation_bps`.
 
 use crate::{
+    account_batch::AccountSnapshot,
+    aggregation::AggregationStrategy,
     error::{OracleError, Result},
-    types::{PriceData, PriceSource, OracleHealth},
-    pyth_client::PythClient,
-    switchboard_client::SwitchboardClient,
+    types::{FeedStatus, PriceData, PriceSource, OracleHealth, SourceFetchStats},
+    oracle_source::{OracleSource, SourceRole},
     config::OracleConfig,
+    onchain::OnChainValidator,
+    subscriptions::{LivePrices, SubscriptionTarget},
+    rpc_pool::RpcPool,
 };
+use oracle_consensus::{self as consensus, ConsensusError, ValidationParams};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use tracing::{debug, warn, error};
 
 /// Price aggregator that combines multiple oracle sources
 pub struct PriceAggregator {
-    /// Pyth client
-    pyth: PythClient,
+    /// Oracle sources, in the order they were added
+    sources: Vec<Arc<dyn OracleSource>>,
     
-    /// Switchboard client
-    switchboard: SwitchboardClient,
+    /// RPC endpoints for batched reads across all oracles
+    rpc_pool: Arc<RpcPool>,
+    
+    /// Upper bound on a single RPC request
+    rpc_timeout: Duration,
+    
+    /// Prices pushed by account subscriptions
+    live: Arc<LivePrices>,
     
     /// Configuration
     config: OracleConfig,
     
     /// Health status of each oracle
     oracle_health: HashMap<PriceSource, OracleHealth>,
+    
+    /// Request counters of each source, updated on every fetch
+    fetch_stats: Mutex<HashMap<PriceSource, SourceFetchStats>>,
+    
+    /// Cross-checks consensus with the on-chain program, when configured
+    onchain: Option<OnChainValidator>,
 }
 
 impl PriceAggregator {
-    /// Create a new price aggregator
+    /// Create a new price aggregator with no sources
     /// 
     /// # Arguments
-    /// * `rpc_url` - Solana RPC endpoint
+    /// * `rpc_url` - Solana RPC endpoint (used for batched account reads)
     /// * `config` - Oracle configuration
     ///
     /// # Example
@@ -54,42 +84,54 @@ impl PriceAggregator {
     /// );
     /// ```
     pub fn new(rpc_url: &str, config: OracleConfig) -> Self {
+        let rpc_timeout = Duration::from_millis(config.rpc_timeout_ms);
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url, rpc_timeout)), config)
+    }
+
+    /// Create a price aggregator reading through a shared RPC pool
+    ///
+    /// # Arguments
+    /// * `rpc_pool` - Health-scored Solana RPC endpoints
+    /// * `config` - Oracle configuration
+    pub fn with_pool(rpc_pool: Arc<RpcPool>, config: OracleConfig) -> Self {
         Self {
-            pyth: PythClient::new(rpc_url),
-            switchboard: SwitchboardClient::new(rpc_url),
+            sources: Vec::new(),
+            rpc_pool,
+            rpc_timeout: Duration::from_millis(config.rpc_timeout_ms),
+            live: Arc::new(LivePrices::default()),
             config,
             oracle_health: HashMap::new(),
+            fetch_stats: Mutex::new(HashMap::new()),
+            onchain: None,
         }
     }
 
-    /// Register a price feed for a symbol across all oracles
+    /// Add an oracle source
     /// 
-    /// This sets up both Pyth and Switchboard for a trading pair.
+    /// Register the source's symbols before adding it.
     ///
-    /// # Arguments
-    /// * `symbol` - Trading pair (e.g., "BTC/USD")
-    /// * `pyth_feed` - Pyth price feed address
-    /// * `switchboard_aggregator` - Switchboard aggregator address
+    /// # Example
+    /// ```rust
+    /// let mut pyth = PythClient::new(rpc_url, timeout);
+    /// pyth.register_feed("BTC/USD".to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU")?;
+    /// aggregator.add_source(pyth);
+    /// ```
+    pub fn add_source(&mut self, source: impl OracleSource + 'static) {
+        debug!("Added oracle source {}", source.name());
+        self.sources.push(Arc::new(source));
+    }
+
+    /// Cross-check every consensus price with the deployed program
+    ///
+    /// The program must hold a config account for every symbol served.
     ///
     /// # Example
     /// ```rust
-    /// aggregator.register_symbol(
-    ///     "BTC/USD",
-    ///     "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix",
-    ///     "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee"
-    /// )?;
+    /// let validator = OnChainValidator::new(rpc_url, payer, timeout)?;
+    /// aggregator.set_onchain_validator(validator);
     /// ```
-    pub fn register_symbol(
-        &mut self,
-        symbol: &str,
-        pyth_feed: &str,
-        switchboard_aggregator: &str,
-    ) -> Result<()> {
-        self.pyth.register_feed(symbol.to_string(), pyth_feed)?;
-        self.switchboard.register_aggregator(symbol.to_string(), switchboard_aggregator)?;
-        
-        debug!("Registered symbol {} with all oracles", symbol);
-        Ok(())
+    pub fn set_onchain_validator(&mut self, validator: OnChainValidator) {
+        self.onchain = Some(validator);
     }
 
     /// Get consensus price for a symbol
@@ -97,7 +139,8 @@ impl PriceAggregator {
     /// This is the main function that combines multiple oracle sources.
     /// 
     /// # Algorithm:
-    /// 1. Fetch prices from all available oracles (Pyth, Switchboard)
+    /// 1. Fetch prices from all available oracles (Pyth, Switchboard),
+    ///    preferring prices pushed by subscriptions over RPC reads
     /// 2. Validate each price individually (freshness, confidence)
     /// 3. Calculate median price (resistant to manipulation)
     /// 4. Check for outliers (prices too far from median)
@@ -119,6 +162,8 @@ impl PriceAggregator {
     ///
     /// # Errors
     /// * `NoPriceData` - No oracles available
+    /// * `InsufficientSources` - Fewer than `min_voting_sources` voters
+    ///   returned a valid price
     /// * `StalePrice` - All prices are too old
     /// * `PriceDeviation` - Sources disagree too much
     ///
@@ -130,34 +175,270 @@ impl PriceAggregator {
     pub async fn get_consensus_price(&self, symbol: &str) -> Result<PriceData> {
         debug!("Fetching consensus price for {}", symbol);
 
-        // Step 1: Fetch prices from all oracles
+        // Step 1: Fetch prices from all oracles that cover the symbol,
+        // concurrently and each within the source deadline
+        let oracles: Vec<_> = self.sources.iter().filter(|o| o.supports(symbol)).collect();
+        let results = futures::future::join_all(oracles.iter().map(|oracle| async move {
+            match self.live_price(symbol, oracle.as_ref()) {
+                Some(price) => Ok(price),
+                None => self.fetch_with_deadline(oracle.as_ref(), symbol).await,
+            }
+        })).await;
+
         let mut prices = Vec::new();
+        let mut references = Vec::new();
         let mut errors = Vec::new();
 
-        // Try Pyth
-        match self.pyth.get_price(symbol).await {
-            Ok(price) => {
-                debug!("Pyth price for {}: ${}", symbol, price.price);
-                prices.push(price);
+        for (oracle, result) in oracles.into_iter().zip(results) {
+            match result {
+                Ok(price) => {
+                    debug!("{} price for {}: ${}", oracle.name(), symbol, price.price);
+                    match oracle.role() {
+                        SourceRole::Voter | SourceRole::Fallback(_) => prices.push(price),
+                        SourceRole::SanityCheck => references.push(price),
+                    }
+                }
+                Err(e) => {
+                    warn!("{} error for {}: {}", oracle.name(), symbol, e);
+                    errors.push((oracle.name(), e));
+                }
             }
-            Err(e) => {
-                warn!("Pyth error for {}: {}", symbol, e);
-                errors.push(("Pyth", e));
+        }
+
+        // The current slot is unknown here; prices are only checked by age
+        let (consensus, inputs) = self.consensus_from_prices(symbol, prices, &references, errors, None)?;
+        self.cross_check(symbol, consensus, &inputs).await
+    }
+
+    /// Every symbol covered by at least one source, sorted
+    pub fn symbols(&self) -> Vec<String> {
+        self.sources.iter()
+            .flat_map(|oracle| oracle.symbols())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Store that account subscriptions push prices into
+    pub fn live_prices(&self) -> Arc<LivePrices> {
+        self.live.clone()
+    }
+
+    /// Every oracle account of every symbol, for `SubscriptionManager`
+    pub fn subscription_targets(&self) -> Vec<SubscriptionTarget> {
+        let mut targets = Vec::new();
+
+        for symbol in self.symbols() {
+            for oracle in &self.sources {
+                if let Some(address) = oracle.account_address(&symbol) {
+                    targets.push(SubscriptionTarget {
+                        address,
+                        commitment: self.config.commitment.resolve(oracle.name(), &symbol),
+                        symbol: symbol.clone(),
+                        oracle: oracle.clone(),
+                    });
+                }
             }
         }
 
-        // Try Switchboard
-        match self.switchboard.get_price(symbol).await {
-            Ok(price) => {
-                debug!("Switchboard price for {}: ${}", symbol, price.price);
-                prices.push(price);
+        targets
+    }
+
+    /// Get consensus prices for many symbols from one account snapshot
+    ///
+    /// Gathers every Pyth feed and Switchboard aggregator of `symbols` into
+    /// chunked `getMultipleAccounts` calls (see `account_batch`), so all
+    /// prices are decoded from the same slot. A refresh of N symbols costs
+    /// `ceil(2N / 100)` RPC calls instead of 2N. Accounts with a live
+    /// subscription price are served from `LivePrices` and not fetched.
+    ///
+    /// # Arguments
+    /// * `symbols` - Trading pairs to price
+    ///
+    /// # Returns
+    /// The snapshot slot and a per-symbol result; one symbol failing
+    /// validation doesn't fail the others
+    ///
+    /// # Errors
+    /// * `SolanaError` / `Timeout` - The batched read itself failed
+    ///
+    /// # Example
+    /// ```rust
+    /// let snapshot = aggregator.get_consensus_prices(aggregator.symbols()).await?;
+    /// for (symbol, price) in &snapshot.prices {
+    ///     println!("{} @ slot {}: {:?}", symbol, snapshot.slot, price);
+    /// }
+    /// ```
+    pub async fn get_consensus_prices(&self, symbols: &[String]) -> Result<ConsensusSnapshot> {
+        // Accounts with a valid pushed price are served from `live` and left
+        // out of the batch; taken once so a disconnect mid-refresh can't drop
+        // an account from both
+        let mut live = HashMap::new();
+        for symbol in symbols {
+            for oracle in &self.sources {
+                if let Some(price) = self.live_price(symbol, oracle.as_ref()) {
+                    live.insert((symbol.as_str(), oracle.source()), price);
+                }
+            }
+        }
+
+        // One snapshot per commitment level; the merged snapshot takes the
+        // highest slot, so finalized reads look a few dozen slots older
+        let mut batches: Vec<(CommitmentConfig, Vec<Pubkey>)> = Vec::new();
+        for symbol in symbols {
+            for oracle in &self.sources {
+                if live.contains_key(&(symbol.as_str(), oracle.source())) {
+                    continue;
+                }
+                let Some(address) = oracle.account_address(symbol) else { continue };
+                let commitment = self.config.commitment.resolve(oracle.name(), symbol);
+
+                match batches.iter_mut().find(|(c, _)| *c == commitment) {
+                    Some((_, addresses)) => addresses.push(address),
+                    None => batches.push((commitment, vec![address])),
+                }
             }
+        }
+
+        let mut accounts = AccountSnapshot::default();
+        for (commitment, addresses) in &batches {
+            let snapshot = self.rpc_pool.fetch_snapshot(addresses, *commitment, self.rpc_timeout).await?;
+            debug!(
+                "Fetched {} accounts at slot {} ({:?})",
+                addresses.len(), snapshot.slot, commitment.commitment
+            );
+            accounts.merge(snapshot);
+        }
+
+        // Live prices are at least as new as the snapshot
+        let slot = live.values()
+            .filter_map(|price| price.slot)
+            .fold(accounts.slot, u64::max);
+
+        let mut prices = BTreeMap::new();
+        for symbol in symbols {
+            let mut symbol_prices = Vec::new();
+            let mut references = Vec::new();
+            let mut errors = Vec::new();
+
+            let oracles: Vec<_> = self.sources.iter().filter(|o| o.supports(symbol)).collect();
+            let results = futures::future::join_all(oracles.iter().map(|oracle| {
+                let accounts = &accounts;
+                let live = &live;
+                async move {
+                    if let Some(price) = live.get(&(symbol.as_str(), oracle.source())) {
+                        return Ok(price.clone());
+                    }
+
+                    // Off-chain sources have no account in the snapshot
+                    match oracle.account_address(symbol) {
+                        // Prices without a publish slot (AMM pools) are as of the snapshot
+                        Some(address) => match accounts.get(&address) {
+                            Some(data) => oracle.decode_account(symbol, data)
+                                .map(|price| PriceData { slot: price.slot.or(Some(accounts.slot)), ..price }),
+                            None => Err(OracleError::NoPriceData(
+                                format!("{} account {} not found", oracle.name(), address)
+                            )),
+                        },
+                        None => self.fetch_with_deadline(oracle.as_ref(), symbol).await,
+                    }
+                }
+            })).await;
+
+            for (oracle, result) in oracles.into_iter().zip(results) {
+                match result {
+                    Ok(price) => match oracle.role() {
+                        SourceRole::Voter | SourceRole::Fallback(_) => symbol_prices.push(price),
+                        SourceRole::SanityCheck => references.push(price),
+                    },
+                    Err(e) => {
+                        warn!("{} error for {}: {}", oracle.name(), symbol, e);
+                        errors.push((oracle.name(), e));
+                    }
+                }
+            }
+
+            let result = match self.consensus_from_prices(
+                symbol, symbol_prices, &references, errors, Some(slot),
+            ) {
+                Ok((consensus, inputs)) => self.cross_check(symbol, consensus, &inputs).await,
+                Err(e) => Err(e),
+            };
+            prices.insert(symbol.clone(), result);
+        }
+
+        Ok(ConsensusSnapshot {
+            slot,
+            prices,
+        })
+    }
+
+    /// Pushed price of `oracle` for `symbol`, if it still passes validation
+    ///
+    /// A quiet account (e.g. an AMM pool that isn't trading) sends no
+    /// notifications, so its live entry ages out; it is then read again
+    /// over RPC instead of dropping out of consensus.
+    fn live_price(&self, symbol: &str, oracle: &dyn OracleSource) -> Option<PriceData> {
+        let price = self.live.get(symbol, oracle.source())?;
+
+        match self.validate_prices(symbol, std::slice::from_ref(&price), None) {
+            Ok(valid) if !valid.is_empty() => Some(price),
+            _ => {
+                debug!("Live {} price for {} is no longer valid, reading it again", oracle.name(), symbol);
+                None
+            }
+        }
+    }
+
+    /// Fetch `symbol` from `oracle`, giving up after the source deadline
+    ///
+    /// Every fetch is counted in `fetch_stats`; a late source is recorded
+    /// as timed out and consensus goes ahead without it.
+    async fn fetch_with_deadline(&self, oracle: &dyn OracleSource, symbol: &str) -> Result<PriceData> {
+        let deadline = Duration::from_millis(self.config.source_deadline_ms);
+        let started = Instant::now();
+
+        let result = tokio::time::timeout(deadline, oracle.get_price(symbol))
+            .await
+            .unwrap_or_else(|_| Err(OracleError::Timeout(format!(
+                "{} missed the {:?} deadline for {}", oracle.name(), deadline, symbol
+            ))));
+
+        let mut stats = self.fetch_stats.lock().unwrap();
+        let stats = stats.entry(oracle.source()).or_default();
+        stats.requests += 1;
+        stats.last_latency_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => stats.last_success = Some(chrono::Utc::now().timestamp()),
             Err(e) => {
-                warn!("Switchboard error for {}: {}", symbol, e);
-                errors.push(("Switchboard", e));
+                stats.failures += 1;
+                if matches!(e, OracleError::Timeout(_)) {
+                    stats.timeouts += 1;
+                }
             }
         }
 
+        result
+    }
+
+    /// Fetch counters of every source that has been asked for a price
+    pub fn fetch_stats(&self) -> HashMap<PriceSource, SourceFetchStats> {
+        self.fetch_stats.lock().unwrap().clone()
+    }
+
+    /// Validate fetched prices and reduce them to a consensus price
+    ///
+    /// Steps 2-6 of `get_consensus_price`, shared with the batched path.
+    /// `prices` come from voters, `references` from sanity-check sources.
+    /// Returns the consensus and the valid prices it was computed from.
+    fn consensus_from_prices(
+        &self,
+        symbol: &str,
+        prices: Vec<PriceData>,
+        references: &[PriceData],
+        errors: Vec<(&str, OracleError)>,
+        current_slot: Option<u64>,
+    ) -> Result<(PriceData, Vec<PriceData>)> {
         // Step 2: Check if we have any prices
         if prices.is_empty() {
             error!("No oracle prices available for {}", symbol);
@@ -166,20 +447,43 @@ impl PriceAggregator {
             ));
         }
 
-        // Step 3: Validate individual prices
-        let valid_prices = self.validate_prices(&prices)?;
+        // Step 3: Validate individual prices; a fallback only votes when
+        // the source it stands in for has no valid price
+        let valid_prices = self.validate_prices(symbol, &prices, current_slot)?;
+        let valid_prices = self.drop_covered_fallbacks(valid_prices);
 
         if valid_prices.is_empty() {
+            if let Some(price) = prices.iter().find(|p| p.status != FeedStatus::Trading) {
+                return Err(OracleError::PythNotTrading(format!(
+                    "No trading price for {}; {:?} is {:?}", symbol, price.source, price.status
+                )));
+            }
             return Err(OracleError::NoPriceData(
                 format!("No valid prices after validation for {}", symbol)
             ));
         }
 
+        // Too few voters left to outvote a bad one
+        let required = self.required_voters(symbol);
+        if valid_prices.len() < required {
+            return Err(OracleError::InsufficientSources(format!(
+                "{} of {} required voting sources valid for {}: {:?}",
+                valid_prices.len(), required, symbol, errors
+            )));
+        }
+
         // Step 4: Calculate median (consensus price)
         let consensus = self.calculate_consensus(&valid_prices)?;
 
-        // Step 5: Validate consensus (check for outliers)
+        // Step 5: Validate consensus (check for outliers and references)
         self.validate_consensus(&valid_prices, &consensus)?;
+        self.check_references(symbol, references, &consensus, current_slot)?;
+
+        // Step 6: The program must accept the exact set being served, whatever
+        // the aggregation strategy (it always checks against the median)
+        let quotes = valid_prices.iter().map(PriceData::to_quote).collect::<Result<Vec<_>>>()?;
+        let now = chrono::Utc::now().timestamp();
+        consensus::validate_consensus(&quotes, now, &self.validation_params(symbol))?;
 
         debug!(
             "Consensus price for {}: ${} (from {} sources)",
@@ -188,86 +492,168 @@ impl PriceAggregator {
             valid_prices.len()
         );
 
-        Ok(consensus)
+        Ok((consensus, valid_prices))
+    }
+
+    /// Ask the deployed program whether it agrees with `consensus`
+    ///
+    /// Only when an `OnChainValidator` is set. A program rejection, or a
+    /// program consensus more than `max_deviation_bps` from ours, fails the
+    /// price; if the simulation itself can't run, our consensus stands.
+    async fn cross_check(&self, symbol: &str, consensus: PriceData, inputs: &[PriceData]) -> Result<PriceData> {
+        let Some(validator) = &self.onchain else { return Ok(consensus) };
+
+        match validator.validate_consensus(symbol, inputs).await {
+            Ok(onchain) => {
+                let deviation = self.calculate_deviation(consensus.price, onchain.price)?;
+                if deviation > self.config.max_deviation_bps {
+                    return Err(OracleError::PriceDeviation(format!(
+                        "{} consensus ${} is {} bps from the program's ${}",
+                        symbol, consensus.price, deviation, onchain.price
+                    )));
+                }
+                Ok(consensus)
+            }
+            Err(e @ OracleError::InvalidPrice(_)) => Err(e),
+            Err(e) => {
+                warn!("On-chain cross-check for {} unavailable: {}", symbol, e);
+                Ok(consensus)
+            }
+        }
+    }
+
+    /// Valid voting prices needed for a consensus on `symbol`
+    ///
+    /// `min_voting_sources`, capped at the voters configured for the
+    /// symbol so single-source symbols still price. A fallback only counts
+    /// when the source it stands in for doesn't cover the symbol.
+    fn required_voters(&self, symbol: &str) -> usize {
+        let covers = |source: PriceSource| self.sources.iter()
+            .any(|o| o.source() == source && o.supports(symbol));
+
+        let voters = self.sources.iter()
+            .filter(|o| o.supports(symbol))
+            .filter(|o| match o.role() {
+                SourceRole::Voter => true,
+                SourceRole::Fallback(primary) => !covers(primary),
+                SourceRole::SanityCheck => false,
+            })
+            .count();
+
+        self.config.min_voting_sources.min(voters).max(1)
+    }
+
+    /// Drop fallback prices whose primary source has a valid price
+    fn drop_covered_fallbacks(&self, prices: Vec<PriceData>) -> Vec<PriceData> {
+        let primary_of = |source: PriceSource| self.sources.iter()
+            .find(|o| o.source() == source)
+            .and_then(|o| match o.role() {
+                SourceRole::Fallback(primary) => Some(primary),
+                _ => None,
+            });
+
+        let present: Vec<PriceSource> = prices.iter().map(|p| p.source).collect();
+        prices.into_iter()
+            .filter(|price| match primary_of(price.source) {
+                Some(primary) if present.contains(&primary) => {
+                    debug!("Dropping {:?} fallback price, {:?} has one", price.source, primary);
+                    false
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Risk limits for `symbol` in the shared consensus crate's format
+    ///
+    /// Negative prices are only accepted for symbols listed in
+    /// `signed_price_symbols`; zero is rejected for every symbol.
+    fn validation_params(&self, symbol: &str) -> ValidationParams {
+        ValidationParams {
+            max_staleness: self.config.max_price_age_seconds,
+            max_confidence_bps: self.config.max_confidence_bps,
+            max_deviation_bps: self.config.max_deviation_bps,
+            allow_negative: self.config.signed_price_symbols.iter().any(|s| s == symbol),
+        }
     }
 
     /// Validate individual prices
     /// 
     /// Checks each price for:
-    /// - Staleness (age < max_price_age_seconds)
-    /// - Confidence (uncertainty < max_confidence_bps)
+    /// - Feed status (`Trading` only; halted or auction prices are whatever
+    ///   was last published)
+    /// - Sign (non-zero; negative only for signed symbols)
+    /// - Staleness (age <= max_price_age_seconds)
+    /// - Slot lag (current_slot - slot <= max_price_age_slots), when both
+    ///   the price's publish slot and the current slot are known
+    /// - Publisher count (live publishers >= min_publishers), when the
+    ///   source reports its publishers
+    /// - Confidence (uncertainty <= max_confidence_bps)
+    ///
+    /// Uses `oracle_consensus::check_quote`, the same check the on-chain
+    /// program runs. Unlike the program, failing prices are dropped rather
+    /// than failing the whole request.
     ///
     /// # Arguments
+    /// * `symbol` - Symbol the prices are for
     /// * `prices` - Raw prices from oracles
+    /// * `current_slot` - Slot the prices were read at, if known
     ///
     /// # Returns
     /// Vector of valid prices
-    fn validate_prices(&self, prices: &[PriceData]) -> Result<Vec<PriceData>> {
+    fn validate_prices(&self, symbol: &str, prices: &[PriceData], current_slot: Option<u64>) -> Result<Vec<PriceData>> {
         let mut valid_prices = Vec::new();
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
+        let params = self.validation_params(symbol);
 
         for price in prices {
-            // Check staleness
-            let age = now - price.timestamp;
-            if age > self.config.max_price_age_seconds {
-                warn!(
-                    "Rejecting stale price from {:?}: {} seconds old",
-                    price.source, age
-                );
+            if price.status != FeedStatus::Trading {
+                warn!("Rejecting {:?} price from {:?}", price.status, price.source);
                 continue;
             }
 
-            // Check confidence
-            let confidence_bps = self.calculate_confidence_bps(price)?;
-            if confidence_bps > self.config.max_confidence_bps {
-                warn!(
-                    "Rejecting high-confidence price from {:?}: {} bps",
-                    price.source, confidence_bps
-                );
-                continue;
+            if let (Some(slot), Some(current_slot)) = (price.slot, current_slot) {
+                let lag = current_slot.saturating_sub(slot);
+                if lag > self.config.max_price_age_slots {
+                    warn!("Rejecting stale price from {:?}: {} slots behind", price.source, lag);
+                    continue;
+                }
             }
 
-            valid_prices.push(price.clone());
+            if let Some(publishers) = price.publishers {
+                if publishers.live < self.config.min_publishers {
+                    warn!(
+                        "Rejecting price from {:?}: {} of {} publishers live (min: {})",
+                        price.source, publishers.live, publishers.total, self.config.min_publishers
+                    );
+                    continue;
+                }
+            }
+
+            match consensus::check_quote(&price.to_quote()?, now, &params) {
+                Ok(()) => valid_prices.push(price.clone()),
+                Err(ConsensusError::StalePriceData) => {
+                    warn!(
+                        "Rejecting stale price from {:?}: {} seconds old",
+                        price.source, now - price.timestamp
+                    );
+                }
+                Err(e) => {
+                    warn!("Rejecting price from {:?}: {}", price.source, e);
+                }
+            }
         }
 
         Ok(valid_prices)
     }
 
-    /// Calculate confidence in basis points
-    /// 
-    /// Converts absolute confidence to percentage.
+    /// Calculate consensus price
     /// 
-    /// # Formula
-    /// ```text
-    /// confidence_bps = (confidence / price) × 10000
-    /// ```
-    ///
-    /// # Example
-    /// ```text
-    /// Price: $50,000
-    /// Confidence: $500
-    /// 
-    /// confidence_bps = (500 / 50000) × 10000 = 100 bps (1%)
-    /// ```
-    fn calculate_confidence_bps(&self, price: &PriceData) -> Result<u64> {
-        if price.price.is_zero() {
-            return Ok(0);
-        }
-
-        let confidence_ratio = price.confidence / price.price;
-        let confidence_bps = (confidence_ratio * Decimal::from(10000))
-            .to_u64()
-            .ok_or_else(|| OracleError::ParseError(
-                "Failed to convert confidence to u64".to_string()
-            ))?;
-
-        Ok(confidence_bps)
-    }
-
-    /// Calculate consensus price (median)
+    /// Uses the symbol's `AggregationStrategy` (see `aggregation`); the
+    /// default is the median described below.
     /// 
     /// # Why Median?
     /// 
@@ -281,6 +667,9 @@ impl PriceAggregator {
     /// 2. If odd number of prices: return middle one
     /// 3. If even number: return average of two middle ones
     ///
+    /// Delegates to `oracle_consensus::median` (8-decimal fixed point), so
+    /// the result matches the on-chain program exactly.
+    ///
     /// # Example
     /// ```text
     /// Prices: [$50,000, $50,100, $49,900]
@@ -294,42 +683,17 @@ impl PriceAggregator {
     /// # Returns
     /// Consensus price with source set to `Aggregate`
     fn calculate_consensus(&self, prices: &[PriceData]) -> Result<PriceData> {
-        if prices.is_empty() {
-            return Err(OracleError::NoPriceData("No prices to aggregate".to_string()));
-        }
-
-        // Sort prices by value
-        let mut sorted_prices = prices.to_vec();
-        sorted_prices.sort_by(|a, b| a.price.cmp(&b.price));
-
-        let len = sorted_prices.len();
-        let (consensus_price, consensus_confidence) = if len % 2 == 1 {
-            // Odd number: return middle price
-            let mid = &sorted_prices[len / 2];
-            (mid.price, mid.confidence)
-        } else {
-            // Even number: average the two middle prices
-            let mid1 = &sorted_prices[len / 2 - 1];
-            let mid2 = &sorted_prices[len / 2];
-            (
-                (mid1.price + mid2.price) / Decimal::from(2),
-                (mid1.confidence + mid2.confidence) / Decimal::from(2),
-            )
-        };
+        let first = prices.first()
+            .ok_or_else(|| OracleError::NoPriceData("No prices to aggregate".to_string()))?;
 
-        // Use the most recent timestamp
-        let latest_timestamp = prices.iter()
-            .map(|p| p.timestamp)
-            .max()
-            .unwrap_or(0);
-
-        Ok(PriceData {
-            symbol: prices[0].symbol.clone(),
-            price: consensus_price,
-            confidence: consensus_confidence,
-            timestamp: latest_timestamp,
-            source: PriceSource::Aggregate,
-        })
+        self.aggregation_strategy(&first.symbol).aggregate(prices)
+    }
+
+    /// Aggregation strategy configured for `symbol`
+    fn aggregation_strategy(&self, symbol: &str) -> &AggregationStrategy {
+        self.config.symbol_aggregation.iter()
+            .find(|(s, _)| s == symbol)
+            .map_or(&self.config.aggregation, |(_, strategy)| strategy)
     }
 
     /// Validate consensus against individual prices
@@ -388,6 +752,41 @@ impl PriceAggregator {
         Ok(())
     }
 
+    /// Reject consensus that strays from a sanity-check reference
+    ///
+    /// Voters agreeing with each other but not with the wider market is the
+    /// signature of a manipulated oracle. References that fail validation
+    /// (stale, zero) are skipped, so a broken reference never blocks prices.
+    ///
+    /// # Errors
+    /// * `PriceDeviation` - A reference deviates more than `max_reference_deviation_bps`
+    fn check_references(
+        &self,
+        symbol: &str,
+        references: &[PriceData],
+        consensus: &PriceData,
+        current_slot: Option<u64>,
+    ) -> Result<()> {
+        for reference in self.validate_prices(symbol, references, current_slot)? {
+            let deviation = self.calculate_deviation(reference.price, consensus.price)?;
+
+            if deviation > self.config.max_reference_deviation_bps {
+                return Err(OracleError::PriceDeviation(format!(
+                    "Consensus for {} deviates {} bps from {:?} reference ${} (max: {})",
+                    symbol,
+                    deviation,
+                    reference.source,
+                    reference.price,
+                    self.config.max_reference_deviation_bps
+                )));
+            }
+
+            debug!("{:?} reference deviation for {}: {} bps", reference.source, symbol, deviation);
+        }
+
+        Ok(())
+    }
+
     /// Calculate price deviation in basis points
     /// 
     /// # Formula
@@ -395,19 +794,22 @@ impl PriceAggregator {
     /// deviation = |price1 - price2| / price2 × 10000
     /// ```
     fn calculate_deviation(&self, price1: Decimal, price2: Decimal) -> Result<u64> {
-        if price2.is_zero() {
-            return Ok(0);
-        }
-
-        let diff = (price1 - price2).abs();
-        let ratio = diff / price2;
-        let deviation_bps = (ratio * Decimal::from(10000))
-            .to_u64()
-            .ok_or_else(|| OracleError::ParseError(
-                "Failed to convert deviation to u64".to_string()
-            ))?;
+        let to_fixed = |price: Decimal| -> Result<i64> {
+            PriceData {
+                symbol: String::new(),
+                price,
+                confidence: Decimal::ZERO,
+                timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
+                source: PriceSource::Aggregate,
+            }
+            .to_quote()
+            .map(|q| q.price)
+        };
 
-        Ok(deviation_bps)
+        Ok(consensus::deviation_bps(to_fixed(price1)?, to_fixed(price2)?)?)
     }
 
     /// Perform health check on all oracles
@@ -425,29 +827,18 @@ impl PriceAggregator {
     pub async fn health_check(&mut self) -> HashMap<PriceSource, OracleHealth> {
         let mut health = HashMap::new();
 
-        // Check Pyth
-        let pyth_healthy = self.pyth.health_check().await;
-        health.insert(
-            PriceSource::Pyth,
-            OracleHealth {
-                source: PriceSource::Pyth,
-                is_healthy: pyth_healthy,
-                last_update: chrono::Utc::now().timestamp(),
-                error_count: if pyth_healthy { 0 } else { 1 },
-            },
-        );
-
-        // Check Switchboard
-        let sb_healthy = self.switchboard.health_check().await;
-        health.insert(
-            PriceSource::Switchboard,
-            OracleHealth {
-                source: PriceSource::Switchboard,
-                is_healthy: sb_healthy,
-                last_update: chrono::Utc::now().timestamp(),
-                error_count: if sb_healthy { 0 } else { 1 },
-            },
-        );
+        for oracle in &self.sources {
+            let is_healthy = oracle.health_check().await;
+            health.insert(
+                oracle.source(),
+                OracleHealth {
+                    source: oracle.source(),
+                    is_healthy,
+                    last_update: chrono::Utc::now().timestamp(),
+                    error_count: if is_healthy { 0 } else { 1 },
+                },
+            );
+        }
 
         self.oracle_health = health.clone();
         health
@@ -459,9 +850,55 @@ impl PriceAggregator {
     }
 }
 
+/// Consensus prices decoded from one account snapshot
+#[derive(Debug)]
+pub struct ConsensusSnapshot {
+    /// Slot every oracle account was read at
+    pub slot: u64,
+
+    /// Consensus price (or the reason there is none) per symbol
+    pub prices: BTreeMap<String, Result<PriceData>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        config::CommitmentPolicy,
+        pyth_client::PythClient,
+        resilience::Resilient,
+        switchboard_client::SwitchboardClient,
+        types::PublisherStats,
+    };
+    use oracle_consensus::Quote;
+    use solana_sdk::commitment_config::CommitmentLevel;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn test_config() -> OracleConfig {
+        OracleConfig {
+            max_price_age_seconds: 30,
+            max_price_age_slots: 75,
+            max_confidence_bps: 100,
+            max_deviation_bps: 100,
+            max_reference_deviation_bps: 200,
+            pyth_publisher_components: false,
+            min_publishers: 0,
+            min_pool_liquidity: 50_000,
+            pool_probe_size: 1_000,
+            signed_price_symbols: vec![],
+            rpc_timeout_ms: 5_000,
+            source_max_attempts: 3,
+            retry_base_delay_ms: 100,
+            retry_max_delay_ms: 2_000,
+            breaker_failure_threshold: 5,
+            breaker_cooldown_ms: 30_000,
+            source_deadline_ms: 10_000,
+            min_voting_sources: 2,
+            commitment: CommitmentPolicy::default(),
+            aggregation: AggregationStrategy::Median,
+            symbol_aggregation: vec![],
+        }
+    }
 
     #[test]
     fn test_median_odd_count() {
@@ -472,6 +909,9 @@ mod tests {
                 price: Decimal::from(100),
                 confidence: Decimal::from(1),
                 timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
                 source: PriceSource::Pyth,
             },
             PriceData {
@@ -479,6 +919,9 @@ mod tests {
                 price: Decimal::from(200),
                 confidence: Decimal::from(1),
                 timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
                 source: PriceSource::Switchboard,
             },
             PriceData {
@@ -486,15 +929,14 @@ mod tests {
                 price: Decimal::from(150),
                 confidence: Decimal::from(1),
                 timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
                 source: PriceSource::Pyth,
             },
         ];
 
-        let config = OracleConfig {
-            max_price_age_seconds: 30,
-            max_confidence_bps: 100,
-            max_deviation_bps: 100,
-        };
+        let config = test_config();
 
         let aggregator = PriceAggregator::new("http://localhost", config);
         let consensus = aggregator.calculate_consensus(&prices).unwrap();
@@ -511,6 +953,9 @@ mod tests {
                 price: Decimal::from(100),
                 confidence: Decimal::from(1),
                 timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
                 source: PriceSource::Pyth,
             },
             PriceData {
@@ -518,15 +963,14 @@ mod tests {
                 price: Decimal::from(200),
                 confidence: Decimal::from(1),
                 timestamp: 0,
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
                 source: PriceSource::Switchboard,
             },
         ];
 
-        let config = OracleConfig {
-            max_price_age_seconds: 30,
-            max_confidence_bps: 100,
-            max_deviation_bps: 100,
-        };
+        let config = test_config();
 
         let aggregator = PriceAggregator::new("http://localhost", config);
         let consensus = aggregator.calculate_consensus(&prices).unwrap();
@@ -537,11 +981,7 @@ mod tests {
 
     #[test]
     fn test_deviation_calculation() {
-        let config = OracleConfig {
-            max_price_age_seconds: 30,
-            max_confidence_bps: 100,
-            max_deviation_bps: 100,
-        };
+        let config = test_config();
 
         let aggregator = PriceAggregator::new("http://localhost", config);
 
@@ -553,4 +993,644 @@ mod tests {
         // |50500 - 50000| / 50000 × 10000 = 500 / 50000 × 10000 = 100 bps
         assert_eq!(deviation, 100);
     }
+
+    /// Off-chain source quoting fixed prices
+    struct FixedSource {
+        source: PriceSource,
+        role: SourceRole,
+        prices: HashMap<String, Decimal>,
+    }
+
+    #[async_trait::async_trait]
+    impl OracleSource for FixedSource {
+        fn name(&self) -> &'static str {
+            "Fixed"
+        }
+
+        fn source(&self) -> PriceSource {
+            self.source
+        }
+
+        fn role(&self) -> SourceRole {
+            self.role
+        }
+
+        fn symbols(&self) -> Vec<String> {
+            self.prices.keys().cloned().collect()
+        }
+
+        async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+            Ok(PriceData {
+                symbol: symbol.to_string(),
+                price: self.prices[symbol],
+                confidence: Decimal::ONE,
+                timestamp: chrono::Utc::now().timestamp(),
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
+                source: self.source,
+            })
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pluggable_sources() {
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        for (source, price) in [(PriceSource::Pyth, 50_000), (PriceSource::Switchboard, 50_010)] {
+            aggregator.add_source(FixedSource {
+                source,
+                role: SourceRole::Voter,
+                prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+            });
+        }
+
+        assert_eq!(aggregator.symbols(), vec!["BTC/USD".to_string()]);
+        assert!(aggregator.subscription_targets().is_empty());
+
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_005));
+
+        // No source covers the symbol
+        assert!(matches!(
+            aggregator.get_consensus_price("ETH/USD").await,
+            Err(OracleError::NoPriceData(_))
+        ));
+
+        let health = aggregator.health_check().await;
+        assert_eq!(health.len(), 2);
+        assert!(health.values().all(|h| h.is_healthy));
+    }
+
+    #[tokio::test]
+    async fn test_per_symbol_strategy() {
+        let config = OracleConfig {
+            symbol_aggregation: vec![(
+                "ETH/USD".to_string(),
+                AggregationStrategy::SourcePriority(vec![PriceSource::Switchboard]),
+            )],
+            ..test_config()
+        };
+        let mut aggregator = PriceAggregator::new("http://localhost", config);
+        for (source, price) in [(PriceSource::Pyth, 3_000), (PriceSource::Switchboard, 3_010), (PriceSource::Hermes, 3_004)] {
+            aggregator.add_source(FixedSource {
+                source,
+                role: SourceRole::Voter,
+                prices: HashMap::from([
+                    ("BTC/USD".to_string(), Decimal::from(price * 10)),
+                    ("ETH/USD".to_string(), Decimal::from(price)),
+                ]),
+            });
+        }
+
+        // BTC/USD keeps the default median, ETH/USD follows Switchboard
+        let btc = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(btc.price, Decimal::from(30_040));
+        let eth = aggregator.get_consensus_price("ETH/USD").await.unwrap();
+        assert_eq!(eth.price, Decimal::from(3_010));
+        assert_eq!(eth.source, PriceSource::Aggregate);
+    }
+
+    /// Answers after `delay_ms`, or never in time; the delay can be
+    /// changed through a clone of the handle
+    struct SlowSource {
+        delay_ms: Arc<AtomicU64>,
+    }
+
+    impl SlowSource {
+        fn new(delay: Duration) -> Self {
+            Self { delay_ms: Arc::new(AtomicU64::new(delay.as_millis() as u64)) }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl OracleSource for SlowSource {
+        fn name(&self) -> &'static str {
+            "Slow"
+        }
+
+        fn source(&self) -> PriceSource {
+            PriceSource::Hermes
+        }
+
+        fn symbols(&self) -> Vec<String> {
+            vec!["BTC/USD".to_string()]
+        }
+
+        async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms.load(Ordering::SeqCst))).await;
+            FixedSource {
+                source: PriceSource::Hermes,
+                role: SourceRole::Voter,
+                prices: HashMap::from([(symbol.to_string(), Decimal::from(50_000))]),
+            }.get_price(symbol).await
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_source_deadline() {
+        let config = OracleConfig {
+            source_deadline_ms: 100,
+            ..test_config()
+        };
+        let mut aggregator = PriceAggregator::new("http://localhost", config);
+        for (source, price) in [(PriceSource::Pyth, 50_000), (PriceSource::Switchboard, 50_010)] {
+            aggregator.add_source(FixedSource {
+                source,
+                role: SourceRole::Voter,
+                prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+            });
+        }
+        aggregator.add_source(SlowSource::new(Duration::from_secs(10)));
+
+        // The slow source is dropped at the deadline instead of stalling consensus
+        let started = Instant::now();
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(consensus.price, Decimal::from(50_005));
+
+        let stats = aggregator.fetch_stats();
+        assert_eq!(stats[&PriceSource::Hermes].timeouts, 1);
+        assert_eq!(stats[&PriceSource::Hermes].failures, 1);
+        assert_eq!(stats[&PriceSource::Pyth].failures, 0);
+        assert!(stats[&PriceSource::Pyth].last_success.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_trips_breaker() {
+        let config = OracleConfig {
+            source_deadline_ms: 100,
+            source_max_attempts: 1,
+            breaker_failure_threshold: 2,
+            breaker_cooldown_ms: 200,
+            ..test_config()
+        };
+        let mut aggregator = PriceAggregator::new("http://localhost", config.clone());
+        for (source, price) in [(PriceSource::Pyth, 50_000), (PriceSource::Switchboard, 50_010)] {
+            aggregator.add_source(FixedSource {
+                source,
+                role: SourceRole::Voter,
+                prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+            });
+        }
+        let slow = SlowSource::new(Duration::from_secs(10));
+        let delay_ms = slow.delay_ms.clone();
+        aggregator.add_source(Resilient::from_config(slow, &config));
+
+        // Each missed deadline cancels the call, which counts as a failure
+        for _ in 0..2 {
+            aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        }
+        assert!(!aggregator.health_check().await[&PriceSource::Hermes].is_healthy);
+
+        // Open: skipped without waiting for the deadline
+        let started = Instant::now();
+        aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(aggregator.fetch_stats()[&PriceSource::Hermes].timeouts, 2);
+
+        // The source recovers; after the cooldown the trial call closes the breaker
+        delay_ms.store(0, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_000));
+        assert!(aggregator.health_check().await[&PriceSource::Hermes].is_healthy);
+    }
+
+    #[tokio::test]
+    async fn test_voting_quorum() {
+        let config = OracleConfig {
+            source_deadline_ms: 100,
+            ..test_config()
+        };
+        let mut aggregator = PriceAggregator::new("http://localhost", config.clone());
+        aggregator.add_source(FixedSource {
+            source: PriceSource::Pyth,
+            role: SourceRole::Voter,
+            prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(50_000))]),
+        });
+        aggregator.add_source(SlowSource::new(Duration::from_secs(10)));
+
+        // One of two voters made the deadline: below the quorum of 2
+        assert!(matches!(
+            aggregator.get_consensus_price("BTC/USD").await,
+            Err(OracleError::InsufficientSources(_))
+        ));
+
+        // A symbol with a single configured voter still prices
+        let mut aggregator = PriceAggregator::new("http://localhost", config);
+        aggregator.add_source(FixedSource {
+            source: PriceSource::Pyth,
+            role: SourceRole::Voter,
+            prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(50_000))]),
+        });
+        assert_eq!(
+            aggregator.get_consensus_price("BTC/USD").await.unwrap().price,
+            Decimal::from(50_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch() {
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        for _ in 0..3 {
+            aggregator.add_source(SlowSource::new(Duration::from_millis(300)));
+        }
+
+        // Three 300ms sources answer together, not one after another
+        let started = Instant::now();
+        aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(800));
+        assert_eq!(aggregator.fetch_stats()[&PriceSource::Hermes].requests, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_source() {
+        let source = |source, role, price: i64| FixedSource {
+            source,
+            role,
+            prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+        };
+
+        // Hermes relays Pyth: with on-chain Pyth priced it doesn't vote again
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        aggregator.add_source(source(PriceSource::Pyth, SourceRole::Voter, 50_000));
+        aggregator.add_source(source(PriceSource::Switchboard, SourceRole::Voter, 50_020));
+        aggregator.add_source(source(PriceSource::Hermes, SourceRole::Fallback(PriceSource::Pyth), 50_000));
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_010));
+
+        // On-chain Pyth rejected (zero price): Hermes takes its seat
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        aggregator.add_source(source(PriceSource::Pyth, SourceRole::Voter, 0));
+        aggregator.add_source(source(PriceSource::Switchboard, SourceRole::Voter, 50_020));
+        aggregator.add_source(source(PriceSource::Hermes, SourceRole::Fallback(PriceSource::Pyth), 50_000));
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_010));
+    }
+
+    #[tokio::test]
+    async fn test_sanity_check_source() {
+        let source = |source, role, price: i64| FixedSource {
+            source,
+            role,
+            prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+        };
+
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        aggregator.add_source(source(PriceSource::Pyth, SourceRole::Voter, 50_000));
+        aggregator.add_source(source(PriceSource::Switchboard, SourceRole::Voter, 50_010));
+        aggregator.add_source(source(PriceSource::Cex, SourceRole::SanityCheck, 50_500));
+
+        // The reference doesn't vote: 1% away is within the 2% limit
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_005));
+
+        // Both voters moved together, the market didn't
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        aggregator.add_source(source(PriceSource::Pyth, SourceRole::Voter, 60_000));
+        aggregator.add_source(source(PriceSource::Switchboard, SourceRole::Voter, 60_010));
+        aggregator.add_source(source(PriceSource::Cex, SourceRole::SanityCheck, 50_000));
+        assert!(matches!(
+            aggregator.get_consensus_price("BTC/USD").await,
+            Err(OracleError::PriceDeviation(_))
+        ));
+    }
+
+    #[test]
+    fn test_subscription_targets() {
+        let mut pyth = PythClient::new("http://localhost", Duration::from_secs(5));
+        pyth.register_feed("BTC/USD".to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU").unwrap();
+        let mut switchboard = SwitchboardClient::new("http://localhost", Duration::from_secs(5));
+        switchboard.register_aggregator("BTC/USD".to_string(), "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee").unwrap();
+
+        let mut aggregator = PriceAggregator::new("http://localhost", test_config());
+        aggregator.add_source(pyth);
+        aggregator.add_source(switchboard);
+
+        let targets = aggregator.subscription_targets();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].oracle.source(), PriceSource::Pyth);
+        assert_eq!(targets[0].address.to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU");
+        assert_eq!(targets[1].oracle.source(), PriceSource::Switchboard);
+        assert!(targets.iter().all(|t| t.symbol == "BTC/USD"));
+        assert!(targets.iter().all(|t| t.commitment == CommitmentConfig::confirmed()));
+    }
+
+    #[tokio::test]
+    async fn test_batched_prices_prefer_live() {
+        let mut pyth = PythClient::new("http://localhost", Duration::from_secs(5));
+        pyth.register_feed("BTC/USD".to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU").unwrap();
+        let mut switchboard = SwitchboardClient::new("http://localhost", Duration::from_secs(5));
+        switchboard.register_aggregator("BTC/USD".to_string(), "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee").unwrap();
+
+        // Nothing listens on the RPC URL; every account must come from `live`
+        let mut aggregator = PriceAggregator::new("http://127.0.0.1:1", test_config());
+        aggregator.add_source(pyth);
+        aggregator.add_source(switchboard);
+
+        let live = aggregator.live_prices();
+        for (source, price, slot) in [(PriceSource::Pyth, 50_000, 100), (PriceSource::Switchboard, 50_010, 101)] {
+            live.update(PriceData {
+                symbol: "BTC/USD".to_string(),
+                price: Decimal::from(price),
+                confidence: Decimal::ONE,
+                timestamp: chrono::Utc::now().timestamp(),
+                slot: Some(slot),
+                status: FeedStatus::Trading,
+                publishers: None,
+                source,
+            }, slot);
+        }
+
+        let snapshot = aggregator.get_consensus_prices(&["BTC/USD".to_string()]).await.unwrap();
+        assert_eq!(snapshot.slot, 101);
+        assert_eq!(snapshot.prices["BTC/USD"].as_ref().unwrap().price, Decimal::from(50_005));
+    }
+
+    #[test]
+    fn test_non_trading_prices_excluded() {
+        let aggregator = PriceAggregator::new("http://localhost", test_config());
+        let price = |value: i64, status: FeedStatus, source: PriceSource| PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from(value),
+            confidence: Decimal::ONE,
+            timestamp: chrono::Utc::now().timestamp(),
+            slot: None,
+            status,
+            publishers: None,
+            source,
+        };
+
+        // The halted feed's last price would otherwise fail the deviation check
+        let prices = vec![
+            price(45_000, FeedStatus::Halted, PriceSource::Pyth),
+            price(50_000, FeedStatus::Trading, PriceSource::Switchboard),
+            price(50_010, FeedStatus::Trading, PriceSource::Hermes),
+        ];
+        let (consensus, inputs) = aggregator.consensus_from_prices("BTC/USD", prices, &[], vec![], None).unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_005));
+        assert_eq!(inputs.len(), 2);
+
+        let prices = vec![price(50_000, FeedStatus::Auction, PriceSource::Pyth)];
+        assert!(matches!(
+            aggregator.consensus_from_prices("BTC/USD", prices, &[], vec![], None),
+            Err(OracleError::PythNotTrading(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stale_live_price_falls_back() {
+        let source = |source, role, price: i64| FixedSource {
+            source,
+            role,
+            prices: HashMap::from([("BTC/USD".to_string(), Decimal::from(price))]),
+        };
+
+        let mut aggregator = PriceAggregator::new("http://127.0.0.1:1", test_config());
+        aggregator.add_source(source(PriceSource::Pyth, SourceRole::Voter, 50_000));
+        aggregator.add_source(source(PriceSource::Switchboard, SourceRole::Voter, 50_010));
+
+        // Last notification a minute ago; the source itself quotes a fresh price
+        let stale_at = chrono::Utc::now().timestamp() - 60;
+        aggregator.live_prices().update(PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from(40_000),
+            confidence: Decimal::ONE,
+            timestamp: stale_at,
+            slot: Some(100),
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Pyth,
+        }, 100);
+
+        let consensus = aggregator.get_consensus_price("BTC/USD").await.unwrap();
+        assert_eq!(consensus.price, Decimal::from(50_005));
+
+        let snapshot = aggregator.get_consensus_prices(&["BTC/USD".to_string()]).await.unwrap();
+        assert_eq!(snapshot.prices["BTC/USD"].as_ref().unwrap().price, Decimal::from(50_005));
+    }
+
+    #[test]
+    fn test_commitment_policy() {
+        let policy = CommitmentPolicy {
+            default: CommitmentLevel::Confirmed,
+            sources: vec![("switchboard".to_string(), CommitmentLevel::Finalized)],
+            symbols: vec![("BTC/USD".to_string(), CommitmentLevel::Processed)],
+        };
+
+        assert_eq!(policy.resolve("Pyth", "ETH/USD"), CommitmentConfig::confirmed());
+        assert_eq!(policy.resolve("Switchboard", "ETH/USD"), CommitmentConfig::finalized());
+        // Symbol overrides beat source overrides
+        assert_eq!(policy.resolve("Switchboard", "BTC/USD"), CommitmentConfig::processed());
+    }
+
+    #[test]
+    fn test_slot_staleness() {
+        let aggregator = PriceAggregator::new("http://localhost", test_config());
+        let price = |slot: Option<u64>| PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from(50_000),
+            confidence: Decimal::ONE,
+            timestamp: chrono::Utc::now().timestamp(),
+            slot,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Pyth,
+        };
+        let prices = vec![price(Some(1_000)), price(Some(1_100)), price(None)];
+
+        // 100 slots behind is over the 75-slot limit; no slot is age-checked only
+        let valid = aggregator.validate_prices("BTC/USD", &prices, Some(1_100)).unwrap();
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].slot, Some(1_100));
+
+        assert_eq!(aggregator.validate_prices("BTC/USD", &prices, None).unwrap().len(), 3);
+
+        let consensus = aggregator.calculate_consensus(&valid).unwrap();
+        assert_eq!(consensus.slot, Some(1_100));
+    }
+
+    #[test]
+    fn test_min_publishers() {
+        let config = OracleConfig {
+            min_publishers: 3,
+            ..test_config()
+        };
+        let aggregator = PriceAggregator::new("http://localhost", config);
+        let price = |live: Option<u32>| PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from(50_000),
+            confidence: Decimal::ONE,
+            timestamp: chrono::Utc::now().timestamp(),
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: live.map(|live| PublisherStats { live, total: 10, dispersion: Decimal::ONE }),
+            source: PriceSource::Pyth,
+        };
+
+        // Sources without publisher stats aren't held to the minimum
+        let prices = vec![price(Some(2)), price(Some(3)), price(None)];
+        let valid = aggregator.validate_prices("BTC/USD", &prices, None).unwrap();
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].publishers.unwrap().live, 3);
+    }
+
+    #[test]
+    fn test_zero_and_negative_prices() {
+        let config = OracleConfig {
+            signed_price_symbols: vec!["BTC-PERP/BASIS".to_string()],
+            ..test_config()
+        };
+        let strategies: Vec<PriceAggregator> = [
+            AggregationStrategy::Median,
+            AggregationStrategy::ConfidenceWeightedMedian,
+            AggregationStrategy::TrimmedMean { trim_percent: 20 },
+            AggregationStrategy::SourcePriority(vec![PriceSource::Pyth]),
+        ]
+            .into_iter()
+            .map(|aggregation| PriceAggregator::new("http://localhost", OracleConfig { aggregation, ..test_config() }))
+            .collect();
+        let aggregator = PriceAggregator::new("http://localhost", config);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let price = |price: i64| PriceData {
+            symbol: "TEST".to_string(),
+            price: Decimal::from(price),
+            confidence: Decimal::ZERO,
+            timestamp: now,
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Pyth,
+        };
+        let prices = vec![price(0), price(-50), price(50)];
+
+        // Zero is never valid; negative only for signed symbols
+        let valid = aggregator.validate_prices("BTC/USD", &prices, None).unwrap();
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0].price, Decimal::from(50));
+
+        let valid = aggregator.validate_prices("BTC-PERP/BASIS", &prices, None).unwrap();
+        assert_eq!(valid.len(), 2);
+
+        // A zero base has no relative deviation
+        assert!(matches!(
+            aggregator.calculate_deviation(Decimal::from(1), Decimal::ZERO),
+            Err(OracleError::InvalidPrice(_))
+        ));
+    }
+
+    /// Differential test: the backend pipeline (Decimal in, filter, median,
+    /// deviation check) must agree with the program's pipeline
+    /// (`oracle_consensus::validate_consensus` on 8-decimal fixed point)
+    /// for the prices the backend keeps.
+    #[test]
+    fn test_parity_with_program_consensus() {
+        let config = test_config();
+        let params = ValidationParams {
+            max_staleness: 30,
+            max_confidence_bps: 100,
+            max_deviation_bps: 100,
+            allow_negative: false,
+        };
+        let strategies: Vec<PriceAggregator> = [
+            AggregationStrategy::Median,
+            AggregationStrategy::ConfidenceWeightedMedian,
+            AggregationStrategy::TrimmedMean { trim_percent: 20 },
+            AggregationStrategy::SourcePriority(vec![PriceSource::Pyth]),
+        ]
+            .into_iter()
+            .map(|aggregation| PriceAggregator::new("http://localhost", OracleConfig { aggregation, ..test_config() }))
+            .collect();
+        let aggregator = PriceAggregator::new("http://localhost", config);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        // Deterministic LCG so failures are reproducible
+        let mut seed: u64 = 0x5eed;
+        let mut next = |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+
+        for case in 0..2_000 {
+            let count = 1 + next(5) as usize;
+            let base: i64 = 1 + next(10_000_000_000_000) as i64;
+            let quotes: Vec<Quote> = (0..count)
+                .map(|_| {
+                    // Spread up to ±2% and confidence up to 2%
+                    let spread = (base as i128 * (next(401) as i128 - 200) / 10_000) as i64;
+                    let price = base + spread;
+                    Quote {
+                        price,
+                        confidence: (price as u128 * next(201) as u128 / 10_000) as u64,
+                        expo: -8,
+                        timestamp: now - next(40) as i64,
+                    }
+                })
+                .collect();
+
+            let prices: Vec<PriceData> = quotes.iter()
+                .map(|q| PriceData::from_quote("TEST", q, PriceSource::Pyth).unwrap())
+                .collect();
+
+            let valid_prices = aggregator.validate_prices("TEST", &prices, None).unwrap();
+            let valid_quotes: Vec<Quote> = quotes.iter()
+                .filter(|q| consensus::check_quote(q, now, &params).is_ok())
+                .copied()
+                .collect();
+            assert_eq!(valid_prices.len(), valid_quotes.len(), "case {}", case);
+
+            if valid_prices.is_empty() {
+                continue;
+            }
+
+            let backend = aggregator.calculate_consensus(&valid_prices)
+                .and_then(|c| aggregator.validate_consensus(&valid_prices, &c).map(|_| c));
+            let program = consensus::validate_consensus(&valid_quotes, now, &params);
+
+            match (backend, &program) {
+                (Ok(backend), Ok(program)) => {
+                    assert_eq!(backend.to_quote().unwrap().price, program.quote.price, "case {}", case);
+                }
+                (Err(OracleError::PriceDeviation(_)), Err(ConsensusError::PriceDeviationTooLarge)) => {}
+                (backend, program) => {
+                    panic!("case {}: backend {:?} vs program {:?}", case, backend, program);
+                }
+            }
+
+            // Full per-quote filtering path, every strategy: whatever is
+            // served, the program accepts the exact set it was computed from
+            for aggregator in &strategies {
+                let served = aggregator.consensus_from_prices("TEST", prices.clone(), &[], vec![], None);
+                let Ok((served, inputs)) = served else {
+                    if matches!(aggregator.config.aggregation, AggregationStrategy::Median) {
+                        assert!(program.is_err(), "case {}: median rejected a set the program accepts", case);
+                    }
+                    continue;
+                };
+
+                let inputs: Vec<Quote> = inputs.iter().map(|p| p.to_quote().unwrap()).collect();
+                let accepted = consensus::validate_consensus(&inputs, now, &params)
+                    .unwrap_or_else(|e| panic!("case {}: {:?} served a set the program rejects: {}", case, aggregator.config.aggregation, e));
+
+                if matches!(aggregator.config.aggregation, AggregationStrategy::Median) {
+                    assert_eq!(served.to_quote().unwrap().price, accepted.quote.price, "case {}", case);
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
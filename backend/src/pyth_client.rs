@@ -1,25 +1,65 @@
 
-use crate::{error::{OracleError, Result}, types::{PriceData, PriceSource}};
-use solana_client::rpc_client::RpcClient;
+use crate::{config::CommitmentPolicy, error::{OracleError, Result}, oracle_source::OracleSource, rpc_pool::RpcPool, types::{scale_by_exponent, FeedStatus, PriceData, PriceSource, PublisherStats}};
+use async_trait::async_trait;
 use solana_sdk::pubkey::Pubkey;
-use pyth_sdk_solana::state::load_price_account;
+use pyth_sdk_solana::state::{load_price_account, PriceStatus, SolanaPriceAccount};
 use rust_decimal::Decimal;
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc, time::Duration};
 use tracing::{debug, warn};
 
+/// A component published more than this many slots before the aggregate
+/// didn't take part in it
+const MAX_COMPONENT_SLOT_LAG: u64 = 25;
+
 pub struct PythClient {
-    rpc_client: RpcClient,
+    rpc_pool: Arc<RpcPool>,
     price_feeds: std::collections::HashMap<String, Pubkey>,
+    /// Upper bound on a single RPC request
+    timeout: Duration,
+    /// Commitment of account reads
+    commitment: CommitmentPolicy,
+    /// Decode per-publisher component prices into `PriceData::publishers`
+    decode_components: bool,
 }
 
 impl PythClient {
-    pub fn new(rpc_url: &str) -> Self {
+    /// Create a new client
+    ///
+    /// # Arguments
+    /// * `rpc_url` - Solana RPC endpoint
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn new(rpc_url: &str, timeout: Duration) -> Self {
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url, timeout)), timeout)
+    }
+
+    /// Create a client reading through a shared RPC pool
+    ///
+    /// # Arguments
+    /// * `rpc_pool` - Health-scored Solana RPC endpoints
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn with_pool(rpc_pool: Arc<RpcPool>, timeout: Duration) -> Self {
         Self {
-            rpc_client: RpcClient::new(rpc_url.to_string()),
+            rpc_pool,
             price_feeds: std::collections::HashMap::new(),
+            timeout,
+            decode_components: false,
+            commitment: CommitmentPolicy::default(),
         }
     }
 
+    /// Read accounts at the commitment `policy` gives this source
+    pub fn set_commitment(&mut self, policy: CommitmentPolicy) {
+        self.commitment = policy;
+    }
+
+    /// Also decode publisher component prices
+    ///
+    /// Fills `PriceData::publishers`, which the aggregator uses to reject
+    /// aggregates backed by too few live publishers.
+    pub fn enable_publisher_components(&mut self) {
+        self.decode_components = true;
+    }
+
     pub fn register_feed(&mut self, symbol: String, feed_address: &str) -> Result<()> {
         let pubkey = Pubkey::from_str(feed_address)
             .map_err(|e| OracleError::ParseError(format!("Invalid pubkey: {}", e)))?;
@@ -30,46 +70,53 @@ impl PythClient {
         Ok(())
     }
 
-    pub async fn get_price(&self, symbol: &str) -> Result<PriceData> {
-        // Step 1: Look up the feed address
-        let feed_address = self.price_feeds
-            .get(symbol)
-            .ok_or_else(|| OracleError::NoPriceData(
-                format!("No Pyth feed registered for {}", symbol)
-            ))?;
-
-        debug!("Fetching Pyth price for {} from {}", symbol, feed_address);
+    /// Address of the feed registered for `symbol`
+    pub fn feed_address(&self, symbol: &str) -> Option<Pubkey> {
+        self.price_feeds.get(symbol).copied()
+    }
 
-        // Step 2: Read account data from Solana
-        let account_data = self.rpc_client
-            .get_account_data(feed_address)
-            .map_err(|e| OracleError::SolanaError(format!(
-                "Failed to fetch account: {}", e
-            )))?;
+    /// Parse raw Pyth price account data into a price for `symbol`
+    ///
+    /// Shared by single reads and batched snapshots.
+    pub fn decode_price(symbol: &str, account_data: &[u8]) -> Result<PriceData> {
+        Self::decode(symbol, account_data, false)
+    }
 
-        // Step 3: Parse Pyth price format
-        let price_account = load_price_account(&account_data)
+    /// `decode_price`, optionally with publisher component stats
+    fn decode(symbol: &str, account_data: &[u8], components: bool) -> Result<PriceData> {
+        let price_account = load_price_account(account_data)
             .map_err(|e| OracleError::ParseError(format!(
                 "Failed to parse Pyth account: {:?}", e
             )))?;
 
         let current_price = price_account.agg;
 
-        // Step 4: Convert to decimal format
-        let price = self.convert_to_decimal(current_price.price, price_account.expo)?;
-        let confidence = self.convert_to_decimal(
+        // Only a Trading aggregate is a price; Halted/Auction/Unknown carry
+        // whatever was last published, so they're tagged and the aggregator
+        // leaves them out of consensus
+        let status = FeedStatus::from(current_price.status);
+
+        // Convert to decimal format
+        let price = Self::convert_to_decimal(current_price.price, price_account.expo)?;
+        let confidence = Self::convert_to_decimal(
             current_price.conf as i64,
             price_account.expo
         )?;
 
-        let timestamp = current_price.pub_slot as i64;
-
-        // Step 5: Create and return price data
+        // `timestamp` is the publish time of the aggregate; `pub_slot` is a
+        // slot number, not seconds
         let price_data = PriceData {
             symbol: symbol.to_string(),
             price,
             confidence,
-            timestamp,
+            timestamp: price_account.timestamp,
+            slot: Some(current_price.pub_slot),
+            status,
+            publishers: if components {
+                Some(Self::publisher_stats(price_account, price)?)
+            } else {
+                None
+            },
             source: PriceSource::Pyth,
         };
 
@@ -79,33 +126,81 @@ impl PythClient {
         Ok(price_data)
     }
 
-    pub async fn get_prices(&self, symbols: &[String]) -> Vec<Result<PriceData>> {
-        let mut results = Vec::new();
-        
-        for symbol in symbols {
-            results.push(self.get_price(symbol).await);
-        }
-        
-        results
+    /// Fetch raw account data without blocking the runtime
+    ///
+    /// The whole call, including the RPC client's own retries, is bounded
+    /// by `timeout`.
+    async fn get_account_data(&self, symbol: &str, address: &Pubkey) -> Result<Vec<u8>> {
+        let commitment = self.commitment.resolve(self.name(), symbol);
+        self.rpc_pool.get_account_data(address, commitment, self.timeout).await
     }
 
-    fn convert_to_decimal(&self, value: i64, expo: i32) -> Result<Decimal> {
-        // Convert to Decimal
-        let mut decimal = Decimal::from(value);
-        if expo < 0 {
-            // Example: expo=-2 means divide by 100
-            let divisor = Decimal::from(10_i64.pow((-expo) as u32));
-            decimal = decimal / divisor;
-        } else {
-            // Example: expo=2 means multiply by 100
-            let multiplier = Decimal::from(10_i64.pow(expo as u32));
-            decimal = decimal * multiplier;
+    /// Count live publishers and measure how far they sit from the aggregate
+    ///
+    /// A component is live if it's trading and was published within
+    /// `MAX_COMPONENT_SLOT_LAG` slots of the aggregate.
+    fn publisher_stats(account: &SolanaPriceAccount, aggregate: Decimal) -> Result<PublisherStats> {
+        let total = (account.num as usize).min(account.comp.len());
+        let min_slot = account.agg.pub_slot.saturating_sub(MAX_COMPONENT_SLOT_LAG);
+
+        let mut live = Vec::new();
+        for component in &account.comp[..total] {
+            if component.agg.status == PriceStatus::Trading && component.agg.pub_slot >= min_slot {
+                live.push(Self::convert_to_decimal(component.agg.price, account.expo)?);
+            }
         }
-        
-        Ok(decimal)
+
+        let dispersion = if live.is_empty() {
+            Decimal::ZERO
+        } else {
+            live.iter().map(|p| (*p - aggregate).abs()).sum::<Decimal>() / Decimal::from(live.len())
+        };
+
+        Ok(PublisherStats {
+            live: live.len() as u32,
+            total: total as u32,
+            dispersion,
+        })
+    }
+
+    fn convert_to_decimal(value: i64, expo: i32) -> Result<Decimal> {
+        // Example: expo=-2 means divide by 100, expo=2 multiply by 100
+        scale_by_exponent(value as i128, expo)
+    }
+}
+
+#[async_trait]
+impl OracleSource for PythClient {
+    fn name(&self) -> &'static str {
+        "Pyth"
+    }
+
+    fn source(&self) -> PriceSource {
+        PriceSource::Pyth
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.price_feeds.keys().cloned().collect()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        // Step 1: Look up the feed address
+        let feed_address = self.price_feeds
+            .get(symbol)
+            .ok_or_else(|| OracleError::NoPriceData(
+                format!("No Pyth feed registered for {}", symbol)
+            ))?;
+
+        debug!("Fetching Pyth price for {} from {}", symbol, feed_address);
+
+        // Step 2: Read account data from Solana
+        let account_data = self.get_account_data(symbol, feed_address).await?;
+
+        // Step 3: Parse Pyth price format
+        Self::decode(symbol, &account_data, self.decode_components)
     }
 
-    pub async fn health_check(&self) -> bool {
+    async fn health_check(&self) -> bool {
         if let Some((symbol, _)) = self.price_feeds.iter().next() {
             match self.get_price(symbol).await {
                 Ok(_) => {
@@ -122,26 +217,135 @@ impl PythClient {
             false
         }
     }
+
+    fn account_address(&self, symbol: &str) -> Option<Pubkey> {
+        self.feed_address(symbol)
+    }
+
+    fn decode_account(&self, symbol: &str, data: &[u8]) -> Result<PriceData> {
+        Self::decode(symbol, data, self.decode_components)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pyth_sdk_solana::state::{AccountType, MAGIC, VERSION_2};
+
+    /// Serialize a Pyth v2 price account
+    fn pyth_account_bytes(price: i64, conf: u64, publish_time: i64, pub_slot: u64, status: PriceStatus) -> Vec<u8> {
+        bytemuck::bytes_of(&pyth_account(price, conf, publish_time, pub_slot, status)).to_vec()
+    }
+
+    /// Pyth v2 price account with no publisher components
+    fn pyth_account(price: i64, conf: u64, publish_time: i64, pub_slot: u64, status: PriceStatus) -> SolanaPriceAccount {
+        let mut account: SolanaPriceAccount = bytemuck::Zeroable::zeroed();
+        account.magic = MAGIC;
+        account.ver = VERSION_2;
+        account.atype = AccountType::Price as u32;
+        account.size = std::mem::size_of::<SolanaPriceAccount>() as u32;
+        account.expo = -8;
+        account.timestamp = publish_time;
+        account.agg.price = price;
+        account.agg.conf = conf;
+        account.agg.status = status;
+        account.agg.pub_slot = pub_slot;
+
+        account
+    }
+
+    #[test]
+    fn test_decode_publish_time_and_slot() {
+        // Slot heights are far above unix time; they must not be mixed up
+        let data = pyth_account_bytes(5_000_000_000_000, 2_500_000_000, 1_702_234_567, 310_000_000, PriceStatus::Trading);
+
+        let price = PythClient::decode_price("BTC/USD", &data).unwrap();
+        assert_eq!(price.price, Decimal::from(50_000));
+        assert_eq!(price.confidence, Decimal::from(25));
+        assert_eq!(price.timestamp, 1_702_234_567);
+        assert_eq!(price.slot, Some(310_000_000));
+        assert_eq!(price.status, FeedStatus::Trading);
+    }
+
+    #[test]
+    fn test_tags_non_trading_status() {
+        for (status, expected) in [
+            (PriceStatus::Halted, FeedStatus::Halted),
+            (PriceStatus::Auction, FeedStatus::Auction),
+            (PriceStatus::Unknown, FeedStatus::Unknown),
+        ] {
+            let data = pyth_account_bytes(5_000_000_000_000, 2_500_000_000, 1_702_234_567, 310_000_000, status);
+
+            let price = PythClient::decode_price("BTC/USD", &data).unwrap();
+            assert_eq!(price.status, expected);
+            assert_eq!(price.price, Decimal::from(50_000));
+        }
+    }
+
+    #[test]
+    fn test_publisher_components() {
+        let mut account = pyth_account(5_000_000_000_000, 2_500_000_000, 1_702_234_567, 1_000, PriceStatus::Trading);
+        // $49,990 and $50,010 live; one halted; one 100 slots old
+        let components = [
+            (4_999_000_000_000, PriceStatus::Trading, 1_000),
+            (5_001_000_000_000, PriceStatus::Trading, 990),
+            (5_000_000_000_000, PriceStatus::Halted, 1_000),
+            (5_000_000_000_000, PriceStatus::Trading, 900),
+        ];
+        account.num = components.len() as u32;
+        for (component, (price, status, pub_slot)) in account.comp.iter_mut().zip(components) {
+            component.agg.price = price;
+            component.agg.status = status;
+            component.agg.pub_slot = pub_slot;
+        }
+        let data = bytemuck::bytes_of(&account).to_vec();
+
+        let stats = PythClient::decode("BTC/USD", &data, true).unwrap().publishers.unwrap();
+        assert_eq!(stats.live, 2);
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.dispersion, Decimal::from(10));
+
+        assert!(PythClient::decode_price("BTC/USD", &data).unwrap().publishers.is_none());
+    }
 
     #[test]
     fn test_decimal_conversion() {
-        let client = PythClient::new("http://localhost");
-        
         // Test case 1: negative exponent
-        let result = client.convert_to_decimal(5000000, -2).unwrap();
+        let result = PythClient::convert_to_decimal(5000000, -2).unwrap();
         assert_eq!(result, Decimal::from(50000));
         
         // Test case 2: positive exponent
-        let result = client.convert_to_decimal(500, 2).unwrap();
+        let result = PythClient::convert_to_decimal(500, 2).unwrap();
         assert_eq!(result, Decimal::from(50000));
         
         // Test case 3: zero exponent
-        let result = client.convert_to_decimal(50000, 0).unwrap();
+        let result = PythClient::convert_to_decimal(50000, 0).unwrap();
         assert_eq!(result, Decimal::from(50000));
+
+        // Test case 4: exponent out of range errors instead of panicking
+        assert!(matches!(PythClient::convert_to_decimal(500, 19), Err(OracleError::ParseError(_))));
+        assert!(matches!(PythClient::convert_to_decimal(500, i32::MIN), Err(OracleError::ParseError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+
+        let mut client = PythClient::new(&url, Duration::from_millis(200));
+        client.register_feed("BTC/USD".to_string(), "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU").unwrap();
+
+        let started = std::time::Instant::now();
+        let result = client.get_price("BTC/USD").await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(matches!(result, Err(OracleError::Timeout(_)) | Err(OracleError::SolanaError(_))));
     }
 }
\ No newline at end of file
@@ -0,0 +1,475 @@
+//! Source Resilience
+//!
+//! Wraps an `OracleSource` with a retry policy and a circuit breaker.
+//! Transient failures (RPC errors, timeouts) are retried with jittered
+//! exponential backoff; a source that keeps failing trips its breaker and
+//! is skipped until a cooldown passes, so a dead source stops adding its
+//! timeout to every consensus call.
+//!
+//! # Breaker states
+//! ```text
+//!          threshold consecutive failures
+//!  Closed ──────────────────────────────▶ Open
+//!    ▲                                     │ cooldown elapsed
+//!    │ trial call succeeds                 ▼
+//!    └─────────────────────────────── HalfOpen ── trial call fails ──▶ Open
+//! ```
+//!
+//! Only transient errors count against the breaker: a stale or non-trading
+//! price means the source answered, just with a price we won't use. A call
+//! that is cancelled before it finishes (e.g. dropped at a caller's
+//! deadline) counts as a failure, and a half-open trial that never reports
+//! back is replaced by a new one after another cooldown.
+//!
+//! # Example
+//! ```rust
+//! let pyth = PythClient::new(rpc_url, timeout);
+//! aggregator.add_source(Resilient::from_config(pyth, &config.oracle_config));
+//! ```
+
+use crate::{
+    config::OracleConfig,
+    error::{OracleError, Result},
+    oracle_source::{OracleSource, SourceRole},
+    types::{PriceData, PriceSource},
+};
+use async_trait::async_trait;
+use rand::Rng;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+/// Whether `error` may go away on retry
+pub fn is_transient(error: &OracleError) -> bool {
+    matches!(error, OracleError::SolanaError(_) | OracleError::Timeout(_))
+}
+
+/// Bounded retries with jittered exponential backoff
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Calls made in total, including the first (1 = no retries)
+    pub max_attempts: u32,
+
+    /// Backoff ceiling before the first retry; doubles on each retry
+    pub base_delay: Duration,
+
+    /// Backoff ceiling never grows past this
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based)
+    ///
+    /// Full jitter: uniform in `[0, min(max_delay, base_delay × 2^retry)]`,
+    /// so sources failing together don't retry in lockstep.
+    fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+
+        Duration::from_micros(rand::thread_rng().gen_range(0..=ceiling.as_micros() as u64))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// One trial call is in flight since `since`
+    HalfOpen { since: Instant },
+}
+
+/// Stops calling a source after consecutive failures
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the breaker
+    failure_threshold: u32,
+
+    /// How long the breaker stays open before a trial call
+    cooldown: Duration,
+
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    /// Whether the breaker is rejecting calls
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.state.lock().unwrap(), BreakerState::Closed { .. })
+    }
+
+    /// Whether a call may go through
+    ///
+    /// Once the cooldown has passed, the first caller gets the trial call
+    /// and everyone else is still rejected until it finishes. A trial still
+    /// unreported after another cooldown is given up on and a new one let
+    /// through.
+    fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::HalfOpen { since } if now >= since + self.cooldown => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::Closed { failures: 0 };
+    }
+
+    /// Count a failure; returns true if it opened the breaker
+    fn record_failure(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        *state = match *state {
+            BreakerState::Closed { failures } if failures + 1 < self.failure_threshold => {
+                BreakerState::Closed { failures: failures + 1 }
+            }
+            BreakerState::Closed { .. } | BreakerState::HalfOpen { .. } => {
+                BreakerState::Open { until: Instant::now() + self.cooldown }
+            }
+            BreakerState::Open { .. } => return false,
+        };
+
+        matches!(*state, BreakerState::Open { .. })
+    }
+}
+
+/// An `OracleSource` with retries and a circuit breaker
+pub struct Resilient<S> {
+    inner: S,
+    policy: RetryPolicy,
+    breaker: CircuitBreaker,
+}
+
+impl<S: OracleSource> Resilient<S> {
+    pub fn new(inner: S, policy: RetryPolicy, breaker: CircuitBreaker) -> Self {
+        Self { inner, policy, breaker }
+    }
+
+    /// Wrap `inner` with the retry and breaker settings from `config`
+    pub fn from_config(inner: S, config: &OracleConfig) -> Self {
+        Self::new(
+            inner,
+            RetryPolicy {
+                max_attempts: config.source_max_attempts,
+                base_delay: Duration::from_millis(config.retry_base_delay_ms),
+                max_delay: Duration::from_millis(config.retry_max_delay_ms),
+            },
+            CircuitBreaker::new(
+                config.breaker_failure_threshold,
+                Duration::from_millis(config.breaker_cooldown_ms),
+            ),
+        )
+    }
+
+    fn circuit_open(&self, symbol: &str) -> OracleError {
+        OracleError::SourceUnavailable(format!(
+            "{} circuit open, skipping {}", self.inner.name(), symbol
+        ))
+    }
+
+    /// Start a call; its outcome must be reported through the guard
+    fn begin(&self) -> CallGuard<'_> {
+        CallGuard { breaker: &self.breaker, name: self.inner.name(), finished: false }
+    }
+}
+
+/// Reports one call's outcome to the breaker, counting a call dropped
+/// before `finish` as a failure
+struct CallGuard<'a> {
+    breaker: &'a CircuitBreaker,
+    name: &'static str,
+    finished: bool,
+}
+
+impl CallGuard<'_> {
+    fn finish(mut self, transient_failure: bool) {
+        self.finished = true;
+        self.record(transient_failure);
+    }
+
+    fn record(&self, transient_failure: bool) {
+        if !transient_failure {
+            self.breaker.record_success();
+        } else if self.breaker.record_failure() {
+            warn!("{} circuit opened for {:?}", self.name, self.breaker.cooldown);
+        }
+    }
+}
+
+impl Drop for CallGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            debug!("{} call cancelled, counting it as failed", self.name);
+            self.record(true);
+        }
+    }
+}
+
+#[async_trait]
+impl<S: OracleSource> OracleSource for Resilient<S> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn source(&self) -> PriceSource {
+        self.inner.source()
+    }
+
+    fn role(&self) -> SourceRole {
+        self.inner.role()
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.inner.symbols()
+    }
+
+    fn supports(&self, symbol: &str) -> bool {
+        self.inner.supports(symbol)
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        if !self.breaker.allow() {
+            return Err(self.circuit_open(symbol));
+        }
+
+        let guard = self.begin();
+        let mut retry = 0;
+        loop {
+            match self.inner.get_price(symbol).await {
+                Err(e) if is_transient(&e) && retry + 1 < self.policy.max_attempts => {
+                    let delay = self.policy.backoff(retry);
+                    debug!("{} error for {}, retrying in {:?}: {}", self.inner.name(), symbol, delay, e);
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => {
+                    guard.finish(matches!(&result, Err(e) if is_transient(e)));
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Batched fetches aren't retried; the batch counts as one call
+    async fn get_prices(&self, symbols: &[String]) -> Vec<Result<PriceData>> {
+        if !self.breaker.allow() {
+            return symbols.iter().map(|symbol| Err(self.circuit_open(symbol))).collect();
+        }
+
+        let guard = self.begin();
+        let results = self.inner.get_prices(symbols).await;
+        let any_ok = results.iter().any(|r| r.is_ok());
+        guard.finish(!any_ok && results.iter().any(|r| matches!(r, Err(e) if is_transient(e))));
+
+        results
+    }
+
+    async fn health_check(&self) -> bool {
+        !self.breaker.is_open() && self.inner.health_check().await
+    }
+
+    fn account_address(&self, symbol: &str) -> Option<Pubkey> {
+        self.inner.account_address(symbol)
+    }
+
+    fn decode_account(&self, symbol: &str, data: &[u8]) -> Result<PriceData> {
+        self.inner.decode_account(symbol, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeedStatus;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails the first `failures` calls with `error`, then returns a price
+    struct FlakySource {
+        failures: u32,
+        error: fn() -> OracleError,
+        calls: AtomicU32,
+    }
+
+    impl FlakySource {
+        fn new(failures: u32, error: fn() -> OracleError) -> Self {
+            Self { failures, error, calls: AtomicU32::new(0) }
+        }
+    }
+
+    #[async_trait]
+    impl OracleSource for FlakySource {
+        fn name(&self) -> &'static str {
+            "Flaky"
+        }
+
+        fn source(&self) -> PriceSource {
+            PriceSource::Pyth
+        }
+
+        fn symbols(&self) -> Vec<String> {
+            vec!["BTC/USD".to_string()]
+        }
+
+        async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)());
+            }
+
+            Ok(PriceData {
+                symbol: symbol.to_string(),
+                price: Decimal::from(50_000),
+                confidence: Decimal::ONE,
+                timestamp: chrono::Utc::now().timestamp(),
+                slot: None,
+                status: FeedStatus::Trading,
+                publishers: None,
+                source: PriceSource::Pyth,
+            })
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    fn timeout() -> OracleError {
+        OracleError::Timeout("test".to_string())
+    }
+
+    fn stale() -> OracleError {
+        OracleError::StalePrice("test".to_string())
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let source = Resilient::new(FlakySource::new(2, timeout), policy(3), CircuitBreaker::new(5, Duration::from_secs(60)));
+        assert!(source.get_price("BTC/USD").await.is_ok());
+        assert_eq!(source.inner.calls.load(Ordering::SeqCst), 3);
+
+        // Attempts are bounded
+        let source = Resilient::new(FlakySource::new(5, timeout), policy(3), CircuitBreaker::new(5, Duration::from_secs(60)));
+        assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::Timeout(_))));
+        assert_eq!(source.inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_bad_price() {
+        let source = Resilient::new(FlakySource::new(5, stale), policy(3), CircuitBreaker::new(1, Duration::from_secs(60)));
+
+        for _ in 0..3 {
+            assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::StalePrice(_))));
+        }
+
+        // Answered every time: no retries, breaker stays closed
+        assert_eq!(source.inner.calls.load(Ordering::SeqCst), 3);
+        assert!(!source.breaker.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let cooldown = Duration::from_millis(50);
+        let source = Resilient::new(FlakySource::new(3, timeout), policy(1), CircuitBreaker::new(2, cooldown));
+
+        assert!(source.get_price("BTC/USD").await.is_err());
+        assert!(!source.breaker.is_open());
+        assert!(source.get_price("BTC/USD").await.is_err());
+        assert!(source.breaker.is_open());
+        assert!(!source.health_check().await);
+
+        // Open: rejected without calling the source
+        assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::SourceUnavailable(_))));
+        assert_eq!(source.inner.calls.load(Ordering::SeqCst), 2);
+
+        // Half-open trial fails: open again
+        tokio::time::sleep(cooldown).await;
+        assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::Timeout(_))));
+        assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::SourceUnavailable(_))));
+
+        // Half-open trial succeeds: closed
+        tokio::time::sleep(cooldown).await;
+        assert!(source.get_price("BTC/USD").await.is_ok());
+        assert!(!source.breaker.is_open());
+        assert_eq!(source.inner.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_half_open_single_trial() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_failure();
+        *breaker.state.lock().unwrap() = BreakerState::Open { until: Instant::now() };
+
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_trial_does_not_wedge() {
+        let cooldown = Duration::from_millis(50);
+        let source = Resilient::new(FlakySource::new(1, timeout), policy(1), CircuitBreaker::new(1, cooldown));
+        assert!(source.get_price("BTC/USD").await.is_err());
+        assert!(source.breaker.is_open());
+
+        // The trial call is dropped before it reports back
+        tokio::time::sleep(cooldown).await;
+        assert!(source.breaker.allow());
+        *source.breaker.state.lock().unwrap() = BreakerState::HalfOpen { since: Instant::now() - cooldown };
+
+        // After another cooldown a new trial goes through and closes the breaker
+        assert!(source.get_price("BTC/USD").await.is_ok());
+        assert!(!source.breaker.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_call_counts_as_failure() {
+        let source = Resilient::new(FlakySource::new(0, timeout), policy(1), CircuitBreaker::new(1, Duration::from_secs(60)));
+
+        // Dropped mid-call, as a caller's deadline would
+        let guard = source.begin();
+        drop(guard);
+        assert!(source.breaker.is_open());
+
+        assert!(matches!(source.get_price("BTC/USD").await, Err(OracleError::SourceUnavailable(_))));
+    }
+
+    #[test]
+    fn test_backoff_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+        };
+
+        for retry in 0..10 {
+            let ceiling = Duration::from_millis(100 * 2u64.pow(retry)).min(policy.max_delay);
+            assert!(policy.backoff(retry) <= ceiling);
+        }
+    }
+}
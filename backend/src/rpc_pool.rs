@@ -0,0 +1,564 @@
+//! RPC Endpoint Pool
+//!
+//! Spreads Solana RPC reads over several providers. Every endpoint keeps a
+//! running score of its latency, error rate and slot lag; each request goes
+//! to the best-scored endpoint and fails over down the list when a call
+//! errors or times out.
+//!
+//! # Scoring
+//! ```text
+//! score = latency_ms + error_rate × ERROR_PENALTY_MS + slot_lag × SLOT_LAG_PENALTY_MS
+//!
+//! latency_ms, error_rate   exponential moving averages (α = 0.2)
+//! slot_lag                 highest slot seen by any endpoint − this endpoint's
+//! ```
+//!
+//! Lower is better. An endpoint more than `MAX_SLOT_LAG` slots behind is
+//! only tried after every caught-up endpoint.
+//!
+//! Failover doesn't multiply the timeout: one call, every endpoint it
+//! tries included, is cut off after `CALL_BUDGET_TIMEOUTS` request timeouts.
+//! A snapshot read makes one request per chunk, so its budget is scaled by
+//! the number of chunks.
+//!
+//! # Minimum context slot
+//! Account reads through the pool pass `minContextSlot` = the highest slot
+//! any earlier read at the same (or stronger) commitment was answered at, so
+//! a price is never served from older state than one already seen. A node
+//! that hasn't caught up rejects the read and the pool fails over.
+//!
+//! # Example
+//! ```rust
+//! let pool = Arc::new(RpcPool::new(&config.solana_rpc_urls, timeout)?);
+//! tokio::spawn(pool.clone().run_health_checks(Duration::from_secs(10)));
+//!
+//! let slot = pool.call(|client| async move {
+//!     client.get_slot().await.map_err(|e| OracleError::SolanaError(e.to_string()))
+//! }).await?;
+//! ```
+
+use crate::{
+    account_batch::{self, AccountSnapshot, MAX_ACCOUNTS_PER_REQUEST},
+    error::{OracleError, Result},
+};
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+/// Weight of the newest sample in the moving averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Score penalty of an endpoint that always fails
+const ERROR_PENALTY_MS: f64 = 5_000.0;
+
+/// Score penalty per slot of lag, roughly one slot time
+const SLOT_LAG_PENALTY_MS: f64 = 400.0;
+
+/// Endpoints further behind than this are tried last
+const MAX_SLOT_LAG: u64 = 50;
+
+/// Request timeouts one call may take in total, across failover attempts
+const CALL_BUDGET_TIMEOUTS: u32 = 2;
+
+/// Running health of one endpoint
+#[derive(Debug, Clone, Copy, Default)]
+struct EndpointStats {
+    /// Moving average of successful call latency (ms)
+    latency_ms: f64,
+
+    /// Moving average of failures (0 = never fails, 1 = always fails)
+    error_rate: f64,
+
+    /// Latest slot the endpoint reported
+    slot: u64,
+
+    requests: u64,
+    failures: u64,
+}
+
+impl EndpointStats {
+    fn record_success(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1_000.0;
+        self.latency_ms = if self.requests == 0 {
+            latency_ms
+        } else {
+            EWMA_ALPHA * latency_ms + (1.0 - EWMA_ALPHA) * self.latency_ms
+        };
+        self.error_rate *= 1.0 - EWMA_ALPHA;
+        self.requests += 1;
+    }
+
+    fn record_failure(&mut self) {
+        self.error_rate = EWMA_ALPHA + (1.0 - EWMA_ALPHA) * self.error_rate;
+        self.requests += 1;
+        self.failures += 1;
+    }
+
+    fn score(&self, slot_lag: u64) -> f64 {
+        self.latency_ms + self.error_rate * ERROR_PENALTY_MS + slot_lag as f64 * SLOT_LAG_PENALTY_MS
+    }
+}
+
+struct Endpoint {
+    url: String,
+    client: Arc<RpcClient>,
+    stats: Mutex<EndpointStats>,
+}
+
+/// Snapshot of one endpoint's health, for monitoring
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointHealth {
+    /// Scheme and host only; paths and queries often carry API keys
+    pub url: String,
+    pub latency_ms: f64,
+    pub error_rate: f64,
+    pub slot_lag: u64,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+/// Health-scored set of Solana RPC endpoints
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+
+    /// Upper bound on one call, failover included
+    call_budget: Duration,
+
+    /// Highest slot an account read was answered at, by commitment level
+    /// (processed, confirmed, finalized)
+    observed_slots: [AtomicU64; 3],
+}
+
+impl RpcPool {
+    /// Create a pool over `urls`, tried in the given order until scored
+    ///
+    /// # Errors
+    /// * `ParseError` - `urls` is empty
+    pub fn new(urls: &[String], timeout: Duration) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(OracleError::ParseError("No RPC endpoints configured".to_string()));
+        }
+
+        Ok(Self {
+            endpoints: urls.iter()
+                .map(|url| Endpoint {
+                    url: url.clone(),
+                    client: Arc::new(RpcClient::new_with_timeout(url.clone(), timeout)),
+                    stats: Mutex::new(EndpointStats::default()),
+                })
+                .collect(),
+            call_budget: timeout.saturating_mul(CALL_BUDGET_TIMEOUTS),
+            observed_slots: Default::default(),
+        })
+    }
+
+    /// Pool with a single endpoint
+    pub fn single(url: &str, timeout: Duration) -> Self {
+        Self::new(&[url.to_string()], timeout).expect("one endpoint")
+    }
+
+    /// Run `op` against the healthiest endpoint, failing over on RPC errors
+    ///
+    /// Only `SolanaError` and `Timeout` count against an endpoint and move
+    /// on to the next one; any other error (e.g. an account that doesn't
+    /// parse) is the same everywhere and is returned immediately.
+    ///
+    /// The whole call, failover included, is bounded by `CALL_BUDGET_TIMEOUTS`
+    /// request timeouts; endpoints not reached by then aren't tried.
+    ///
+    /// # Errors
+    /// The last endpoint's error when every endpoint failed, or `Timeout`
+    /// when the budget ran out
+    pub async fn call<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.call_within(self.call_budget, op).await
+    }
+
+    /// `call` bounded by `budget` instead of the single-request budget
+    async fn call_within<T, F, Fut>(&self, budget: Duration, op: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let deadline = Instant::now() + budget;
+        let mut last_error = None;
+
+        for index in self.ranked() {
+            let endpoint = &self.endpoints[index];
+            let started = Instant::now();
+            let remaining = deadline.saturating_duration_since(started);
+            if remaining.is_zero() {
+                break;
+            }
+
+            let result = tokio::time::timeout(remaining, op(endpoint.client.clone()))
+                .await
+                .unwrap_or_else(|_| Err(OracleError::Timeout(format!(
+                    "RPC call exceeded its {:?} budget", budget
+                ))));
+
+            match result {
+                Ok(value) => {
+                    endpoint.stats.lock().unwrap().record_success(started.elapsed());
+                    return Ok(value);
+                }
+                Err(e @ (OracleError::SolanaError(_) | OracleError::Timeout(_))) => {
+                    warn!("RPC endpoint {} failed, failing over: {}", endpoint.url, e);
+                    endpoint.stats.lock().unwrap().record_failure();
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| OracleError::Timeout(format!(
+            "RPC call exceeded its {:?} budget", budget
+        ))))
+    }
+
+    /// Read one account at `commitment`, no older than any earlier read
+    ///
+    /// # Errors
+    /// * `NoPriceData` - The account doesn't exist
+    /// * `SolanaError` / `Timeout` - Every endpoint failed
+    pub async fn get_account_data(
+        &self,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(commitment),
+            min_context_slot: self.min_context_slot(commitment),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        let response = self.call(|client| {
+            let config = config.clone();
+            async move {
+                tokio::time::timeout(timeout, client.get_account_with_config(address, config))
+                    .await
+                    .map_err(|_| OracleError::Timeout(format!(
+                        "Fetching {} took longer than {:?}", address, timeout
+                    )))?
+                    .map_err(|e| OracleError::SolanaError(format!(
+                        "Failed to fetch account: {}", e
+                    )))
+            }
+        }).await?;
+        self.observe_slot(commitment, response.context.slot);
+
+        response.value
+            .map(|account| account.data)
+            .ok_or_else(|| OracleError::NoPriceData(format!("Account {} not found", address)))
+    }
+
+    /// Read `addresses` into one snapshot (see `account_batch::fetch_snapshot`),
+    /// no older than any earlier read
+    ///
+    /// The call budget covers each `getMultipleAccounts` chunk, so a large
+    /// symbol set doesn't time out (and fail over) a healthy endpoint.
+    pub async fn fetch_snapshot(
+        &self,
+        addresses: &[Pubkey],
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<AccountSnapshot> {
+        let min_context_slot = self.min_context_slot(commitment);
+        let chunks = addresses.len().div_ceil(MAX_ACCOUNTS_PER_REQUEST).max(1) as u32;
+        let budget = self.call_budget.saturating_mul(chunks);
+
+        let snapshot = self.call_within(budget, |client| async move {
+            account_batch::fetch_snapshot(&client, addresses, commitment, min_context_slot, timeout).await
+        }).await?;
+        self.observe_slot(commitment, snapshot.slot);
+
+        Ok(snapshot)
+    }
+
+    /// Oldest slot a read at `commitment` may be answered at
+    pub fn min_context_slot(&self, commitment: CommitmentConfig) -> Option<u64> {
+        let slot = self.observed_slots[Self::level_index(commitment)].load(Ordering::Relaxed);
+        (slot > 0).then_some(slot)
+    }
+
+    /// Record a read answered at `slot`
+    ///
+    /// State seen at a commitment level is also visible at every weaker
+    /// one, so a finalized slot raises the confirmed and processed floors.
+    fn observe_slot(&self, commitment: CommitmentConfig, slot: u64) {
+        for observed in &self.observed_slots[..=Self::level_index(commitment)] {
+            observed.fetch_max(slot, Ordering::Relaxed);
+        }
+    }
+
+    fn level_index(commitment: CommitmentConfig) -> usize {
+        match commitment.commitment {
+            CommitmentLevel::Processed => 0,
+            CommitmentLevel::Confirmed => 1,
+            _ => 2,
+        }
+    }
+
+    /// Ask every endpoint for its slot, updating latency, errors and lag
+    pub async fn refresh_slots(&self) {
+        let results = futures::future::join_all(self.endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            (endpoint.client.get_slot().await, started.elapsed())
+        })).await;
+
+        for (endpoint, (result, latency)) in self.endpoints.iter().zip(results) {
+            let mut stats = endpoint.stats.lock().unwrap();
+            match result {
+                Ok(slot) => {
+                    stats.record_success(latency);
+                    stats.slot = slot;
+                }
+                Err(e) => {
+                    debug!("getSlot failed on {}: {}", endpoint.url, e);
+                    stats.record_failure();
+                }
+            }
+        }
+    }
+
+    /// Refresh slots every `interval`, forever
+    pub async fn run_health_checks(self: Arc<Self>, interval: Duration) {
+        loop {
+            self.refresh_slots().await;
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Current health of every endpoint, in configured order
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        let stats = self.stats();
+        let best_slot = stats.iter().map(|s| s.slot).max().unwrap_or(0);
+
+        self.endpoints.iter()
+            .zip(stats)
+            .map(|(endpoint, stats)| EndpointHealth {
+                url: redact_url(&endpoint.url),
+                latency_ms: stats.latency_ms,
+                error_rate: stats.error_rate,
+                slot_lag: best_slot.saturating_sub(stats.slot),
+                requests: stats.requests,
+                failures: stats.failures,
+            })
+            .collect()
+    }
+
+    /// Endpoint indices, best first
+    fn ranked(&self) -> Vec<usize> {
+        let stats = self.stats();
+        let best_slot = stats.iter().map(|s| s.slot).max().unwrap_or(0);
+
+        let mut ranked: Vec<(bool, f64, usize)> = stats.iter()
+            .enumerate()
+            .map(|(index, stats)| {
+                let lag = best_slot.saturating_sub(stats.slot);
+                (lag > MAX_SLOT_LAG, stats.score(lag), index)
+            })
+            .collect();
+        // Stable: unscored endpoints keep their configured order
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+        ranked.into_iter().map(|(_, _, index)| index).collect()
+    }
+
+    fn stats(&self) -> Vec<EndpointStats> {
+        self.endpoints.iter().map(|e| *e.stats.lock().unwrap()).collect()
+    }
+}
+
+/// Scheme and host of `url`, dropping credentials, path and query
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default()),
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+    use serde_json::{json, Value};
+
+    /// Local JSON-RPC node answering `getSlot` with `slot`, or failing
+    async fn mock_rpc(slot: Option<u64>) -> String {
+        async fn handle(
+            State(slot): State<Option<u64>>,
+            Json(request): Json<Value>,
+        ) -> std::result::Result<Json<Value>, StatusCode> {
+            let slot = slot.ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+            Ok(Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": slot })))
+        }
+
+        let app = Router::new().route("/", post(handle)).with_state(slot);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        url
+    }
+
+    async fn get_slot(pool: &RpcPool) -> Result<u64> {
+        pool.call(|client| async move {
+            client.get_slot().await.map_err(|e| OracleError::SolanaError(e.to_string()))
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        let urls = vec![mock_rpc(None).await, mock_rpc(Some(1_000)).await];
+        let pool = RpcPool::new(&urls, Duration::from_secs(5)).unwrap();
+
+        // First endpoint is down; the call lands on the second
+        assert_eq!(get_slot(&pool).await.unwrap(), 1_000);
+
+        let health = pool.health();
+        assert_eq!(health[0].failures, 1);
+        assert_eq!(health[1].failures, 0);
+
+        // The failing endpoint is now ranked last
+        assert_eq!(pool.ranked(), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_slot_lag_ranking() {
+        let urls = vec![mock_rpc(Some(900)).await, mock_rpc(Some(1_000)).await];
+        let pool = RpcPool::new(&urls, Duration::from_secs(5)).unwrap();
+
+        pool.refresh_slots().await;
+
+        let health = pool.health();
+        assert_eq!(health[0].slot_lag, 100);
+        assert_eq!(health[1].slot_lag, 0);
+        assert_eq!(pool.ranked(), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoints_down() {
+        let urls = vec![mock_rpc(None).await, mock_rpc(None).await];
+        let pool = RpcPool::new(&urls, Duration::from_secs(5)).unwrap();
+
+        assert!(matches!(get_slot(&pool).await, Err(OracleError::SolanaError(_))));
+        assert!(pool.health().iter().all(|h| h.failures == 1));
+
+        // Errors that aren't the endpoint's fault don't fail over
+        let result: Result<()> = pool.call(|_| async {
+            Err(OracleError::ParseError("bad account".to_string()))
+        }).await;
+        assert!(matches!(result, Err(OracleError::ParseError(_))));
+        assert!(pool.health().iter().all(|h| h.requests == 1));
+    }
+
+    #[tokio::test]
+    async fn test_call_budget() {
+        let urls = vec!["http://a.invalid".to_string(), "http://b.invalid".to_string()];
+        let pool = RpcPool::new(&urls, Duration::from_millis(100)).unwrap();
+
+        // Hanging endpoints share one budget instead of a timeout each
+        let started = Instant::now();
+        let result: Result<()> = pool.call(|_| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        }).await;
+        assert!(matches!(result, Err(OracleError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert_eq!(pool.health()[0].failures + pool.health()[1].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_budget_per_chunk() {
+        /// Answers `getMultipleAccounts` after 150 ms, every account at slot 10
+        async fn slow_accounts(Json(request): Json<Value>) -> Json<Value> {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            let keys = request["params"][0].as_array().unwrap().len();
+            Json(json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "context": { "slot": 10 },
+                    "value": (0..keys).map(|_| json!({
+                        "data": ["AQID", "base64"],
+                        "executable": false,
+                        "lamports": 1,
+                        "owner": "11111111111111111111111111111111",
+                        "rentEpoch": 0,
+                        "space": 3,
+                    })).collect::<Vec<_>>(),
+                },
+            }))
+        }
+
+        let app = Router::new().route("/", post(slow_accounts));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // Three chunks take 450 ms, more than one 400 ms call budget
+        let timeout = Duration::from_millis(200);
+        let pool = RpcPool::single(&url, timeout);
+        let addresses: Vec<Pubkey> = (0..250).map(|_| Pubkey::new_unique()).collect();
+
+        let snapshot = pool.fetch_snapshot(&addresses, CommitmentConfig::confirmed(), timeout).await.unwrap();
+        assert_eq!(snapshot.slot, 10);
+        assert_eq!(snapshot.get(&addresses[249]), Some(&[1, 2, 3][..]));
+        assert_eq!(pool.health()[0].failures, 0);
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("https://mainnet.helius-rpc.com/?api-key=secret"),
+            "https://mainnet.helius-rpc.com"
+        );
+        assert_eq!(redact_url("https://x.solana-mainnet.quiknode.pro/secret/"), "https://x.solana-mainnet.quiknode.pro");
+    }
+
+    #[test]
+    fn test_min_context_slot() {
+        let pool = RpcPool::single("http://localhost", Duration::from_secs(5));
+        assert_eq!(pool.min_context_slot(CommitmentConfig::processed()), None);
+
+        pool.observe_slot(CommitmentConfig::confirmed(), 1_000);
+        assert_eq!(pool.min_context_slot(CommitmentConfig::processed()), Some(1_000));
+        assert_eq!(pool.min_context_slot(CommitmentConfig::confirmed()), Some(1_000));
+        assert_eq!(pool.min_context_slot(CommitmentConfig::finalized()), None);
+
+        // Finalized raises every floor, but never lowers one
+        pool.observe_slot(CommitmentConfig::finalized(), 968);
+        assert_eq!(pool.min_context_slot(CommitmentConfig::confirmed()), Some(1_000));
+        assert_eq!(pool.min_context_slot(CommitmentConfig::finalized()), Some(968));
+    }
+
+    #[test]
+    fn test_endpoint_score() {
+        let mut stats = EndpointStats::default();
+        stats.record_success(Duration::from_millis(100));
+        assert_eq!(stats.score(0), 100.0);
+
+        stats.record_failure();
+        assert!(stats.score(0) > 100.0);
+        assert_eq!(stats.score(1) - stats.score(0), SLOT_LAG_PENALTY_MS);
+    }
+}
@@ -0,0 +1,425 @@
+//! Account Subscriptions
+//!
+//! Push-based price updates over the Solana WebSocket API. One connection
+//! carries an `accountSubscribe` stream per account of every account-based
+//! `OracleSource` (Pyth feeds, Switchboard aggregators); every update is
+//! decoded by its source as it arrives and stored in `LivePrices`, which the
+//! aggregator reads before falling back to RPC.
+//!
+//! # Architecture
+//! ```text
+//! solana_ws_url ──accountSubscribe×N──→ [SubscriptionManager]
+//!                                             ↓ decode
+//!                                        LivePrices  ←── PriceAggregator
+//!                                             ↑
+//!                     disconnect → clear, backoff, reconnect, resubscribe
+//! ```
+//!
+//! # Freshness
+//! Live prices are only served while their stream is connected: on any
+//! disconnect the store is cleared, so the aggregator transparently falls
+//! back to RPC reads until the subscriptions are back.
+
+use crate::{
+    error::{OracleError, Result},
+    oracle_source::OracleSource,
+    types::{PriceData, PriceSource},
+};
+use futures::{stream::select_all, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tracing::{debug, info, warn};
+
+/// First reconnect delay
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest reconnect delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// One account to subscribe to
+#[derive(Clone)]
+pub struct SubscriptionTarget {
+    /// Oracle account address
+    pub address: Pubkey,
+
+    /// Commitment level of the account's updates
+    pub commitment: CommitmentConfig,
+
+    /// Symbol the account prices
+    pub symbol: String,
+
+    /// Oracle that owns the account and decodes its updates
+    pub oracle: Arc<dyn OracleSource>,
+}
+
+/// Latest pushed price of one oracle account
+#[derive(Debug, Clone)]
+pub struct LivePrice {
+    pub price: PriceData,
+
+    /// Slot of the update
+    pub slot: u64,
+}
+
+/// Prices pushed by subscriptions, shared with the aggregator
+#[derive(Debug, Default)]
+pub struct LivePrices {
+    prices: RwLock<HashMap<(String, PriceSource), LivePrice>>,
+}
+
+impl LivePrices {
+    /// Latest pushed price of `source` for `symbol`, if subscribed
+    pub fn get(&self, symbol: &str, source: PriceSource) -> Option<PriceData> {
+        self.prices.read().unwrap()
+            .get(&(symbol.to_string(), source))
+            .map(|live| live.price.clone())
+    }
+
+    /// Number of accounts with a live price
+    pub fn len(&self) -> usize {
+        self.prices.read().unwrap().len()
+    }
+
+    /// Store an update; updates older than the stored slot are ignored
+    pub(crate) fn update(&self, price: PriceData, slot: u64) {
+        let key = (price.symbol.clone(), price.source);
+        let mut prices = self.prices.write().unwrap();
+
+        if prices.get(&key).is_some_and(|live| live.slot > slot) {
+            return;
+        }
+        prices.insert(key, LivePrice { price, slot });
+    }
+
+    /// Forget every live price (the streams are down)
+    fn clear(&self) {
+        self.prices.write().unwrap().clear();
+    }
+}
+
+/// Exponential reconnect delay
+#[derive(Debug)]
+pub(crate) struct Backoff {
+    current: Duration,
+}
+
+impl Backoff {
+    pub(crate) fn new() -> Self {
+        Self { current: MIN_BACKOFF }
+    }
+
+    /// Delay before the next attempt; doubles up to `MAX_BACKOFF`
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(MAX_BACKOFF);
+        delay
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.current = MIN_BACKOFF;
+    }
+}
+
+/// Keeps `accountSubscribe` streams open and `LivePrices` current
+pub struct SubscriptionManager {
+    /// Solana WebSocket endpoint
+    ws_url: String,
+
+    /// Accounts to subscribe to
+    targets: Vec<SubscriptionTarget>,
+
+    /// Store the aggregator reads from
+    live: Arc<LivePrices>,
+}
+
+impl SubscriptionManager {
+    /// Create a new subscription manager
+    ///
+    /// # Arguments
+    /// * `ws_url` - Solana WebSocket endpoint
+    /// * `targets` - Accounts to subscribe to (see `PriceAggregator::subscription_targets`)
+    /// * `live` - Store shared with the aggregator
+    pub fn new(ws_url: &str, targets: Vec<SubscriptionTarget>, live: Arc<LivePrices>) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            targets,
+            live,
+        }
+    }
+
+    /// Run forever: subscribe, apply updates, reconnect with backoff
+    ///
+    /// # Example
+    /// ```rust
+    /// let manager = SubscriptionManager::new(
+    ///     &config.solana_ws_url,
+    ///     aggregator.subscription_targets(),
+    ///     aggregator.live_prices(),
+    /// );
+    /// tokio::spawn(manager.run());
+    /// ```
+    pub async fn run(self) {
+        if self.targets.is_empty() {
+            warn!("No oracle accounts to subscribe to");
+            return;
+        }
+
+        let mut backoff = Backoff::new();
+        loop {
+            match self.stream_updates(&mut backoff).await {
+                Ok(()) => warn!("Account subscriptions closed by {}", self.ws_url),
+                Err(e) => warn!("Account subscriptions failed: {}", e),
+            }
+
+            self.live.clear();
+            let delay = backoff.next_delay();
+            info!("Resubscribing to {} accounts in {:?}", self.targets.len(), delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// One connection: subscribe every target and apply updates until any
+    /// stream ends
+    async fn stream_updates(&self, backoff: &mut Backoff) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url)
+            .await
+            .map_err(|e| OracleError::SolanaError(format!("WebSocket connect failed: {}", e)))?;
+
+        let mut streams = Vec::with_capacity(self.targets.len());
+        let mut unsubscribes = Vec::with_capacity(self.targets.len());
+        for (index, target) in self.targets.iter().enumerate() {
+            let config = RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(target.commitment),
+                ..RpcAccountInfoConfig::default()
+            };
+            let (stream, unsubscribe) = client
+                .account_subscribe(&target.address, Some(config))
+                .await
+                .map_err(|e| OracleError::SolanaError(format!(
+                    "accountSubscribe for {} failed: {}", target.address, e
+                )))?;
+            streams.push(stream.map(move |update| (index, update)));
+            unsubscribes.push(unsubscribe);
+        }
+
+        info!("Subscribed to {} oracle accounts", self.targets.len());
+        backoff.reset();
+
+        let mut updates = select_all(streams);
+        while let Some((index, update)) = updates.next().await {
+            let target = &self.targets[index];
+            let Some(account) = update.value.decode::<Account>() else {
+                warn!("Undecodable update for {} ({})", target.symbol, target.address);
+                continue;
+            };
+
+            match target.oracle.decode_account(&target.symbol, &account.data) {
+                Ok(price) => {
+                    debug!(
+                        "Live {} price for {} at slot {}: ${}",
+                        target.oracle.name(), target.symbol, update.context.slot, price.price
+                    );
+                    // Prices without a publish slot (AMM pools) are as of the notification
+                    let slot = update.context.slot;
+                    self.live.update(PriceData { slot: price.slot.or(Some(slot)), ..price }, slot);
+                }
+                Err(e) => warn!("Failed to decode {} update for {}: {}", target.oracle.name(), target.symbol, e),
+            }
+        }
+
+        drop(updates);
+        for unsubscribe in unsubscribes {
+            unsubscribe().await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeedStatus;
+    use axum::{
+        extract::{ws::{Message, WebSocket, WebSocketUpgrade}, State},
+        response::Response,
+        routing::get,
+        Router,
+    };
+    use base64::Engine;
+    use rust_decimal::Decimal;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn price(value: i64) -> PriceData {
+        PriceData {
+            symbol: "BTC/USD".to_string(),
+            price: Decimal::from(value),
+            confidence: Decimal::ONE,
+            timestamp: 0,
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Pyth,
+        }
+    }
+
+    #[test]
+    fn test_live_prices_keep_newest_slot() {
+        let live = LivePrices::default();
+        live.update(price(50_000), 10);
+        live.update(price(49_000), 9);
+        assert_eq!(live.get("BTC/USD", PriceSource::Pyth).unwrap().price, Decimal::from(50_000));
+
+        live.update(price(50_100), 11);
+        assert_eq!(live.get("BTC/USD", PriceSource::Pyth).unwrap().price, Decimal::from(50_100));
+        assert!(live.get("BTC/USD", PriceSource::Switchboard).is_none());
+
+        live.clear();
+        assert_eq!(live.len(), 0);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(500));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+
+        for _ in 0..10 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), MAX_BACKOFF);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), MIN_BACKOFF);
+    }
+
+    /// Account-based source whose accounts hold a little-endian i64 price
+    struct AccountSource {
+        address: Pubkey,
+    }
+
+    #[async_trait::async_trait]
+    impl OracleSource for AccountSource {
+        fn name(&self) -> &'static str {
+            "Account"
+        }
+
+        fn source(&self) -> PriceSource {
+            PriceSource::Pyth
+        }
+
+        fn symbols(&self) -> Vec<String> {
+            vec!["BTC/USD".to_string()]
+        }
+
+        async fn get_price(&self, _symbol: &str) -> Result<PriceData> {
+            Err(OracleError::SolanaError("RPC is not mocked".to_string()))
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+
+        fn account_address(&self, _symbol: &str) -> Option<Pubkey> {
+            Some(self.address)
+        }
+
+        fn decode_account(&self, _symbol: &str, data: &[u8]) -> Result<PriceData> {
+            let bytes = data.try_into()
+                .map_err(|_| OracleError::ParseError("Expected 8 bytes".to_string()))?;
+            Ok(price(i64::from_le_bytes(bytes)))
+        }
+    }
+
+    /// Answers every `accountSubscribe` and pushes one update priced
+    /// $50,000 + connection number at slot 10 × (connection + 1); the first
+    /// connection is dropped right after its update
+    async fn account_subscribe(ws: WebSocketUpgrade, State(connections): State<Arc<AtomicU64>>) -> Response {
+        let connection = connections.fetch_add(1, Ordering::SeqCst);
+        ws.on_upgrade(move |mut socket: WebSocket| async move {
+            while let Some(Ok(Message::Text(text))) = socket.recv().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                if request["method"] != "accountSubscribe" {
+                    continue;
+                }
+
+                let data = (50_000 + connection as i64).to_le_bytes();
+                let messages = [
+                    json!({ "jsonrpc": "2.0", "result": 7, "id": request["id"] }),
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": "accountNotification",
+                        "params": {
+                            "subscription": 7,
+                            "result": {
+                                "context": { "slot": 10 * (connection + 1) },
+                                "value": {
+                                    "lamports": 1,
+                                    "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                                    "owner": Pubkey::default().to_string(),
+                                    "executable": false,
+                                    "rentEpoch": 0,
+                                    "space": data.len(),
+                                },
+                            },
+                        },
+                    }),
+                ];
+                for message in messages {
+                    socket.send(Message::Text(message.to_string())).await.unwrap();
+                }
+
+                if connection == 0 {
+                    return;
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_after_disconnect() {
+        let connections = Arc::new(AtomicU64::new(0));
+        let app = Router::new()
+            .route("/", get(account_subscribe))
+            .with_state(connections.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let live = Arc::new(LivePrices::default());
+        let address = Pubkey::new_unique();
+        let target = SubscriptionTarget {
+            address,
+            commitment: CommitmentConfig::confirmed(),
+            symbol: "BTC/USD".to_string(),
+            oracle: Arc::new(AccountSource { address }),
+        };
+        let manager = tokio::spawn(SubscriptionManager::new(&url, vec![target], live.clone()).run());
+
+        // The second connection's update arrives after one backoff delay
+        let resubscribed = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(price) = live.get("BTC/USD", PriceSource::Pyth) {
+                    if price.price == Decimal::from(50_001) {
+                        return price;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }).await.expect("updates did not resume after the disconnect");
+
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+        assert_eq!(resubscribed.slot, Some(20));
+        assert_eq!(live.len(), 1);
+
+        manager.abort();
+    }
+}
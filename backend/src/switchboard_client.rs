@@ -1,87 +1,154 @@
 
-use crate::{error::{OracleError, Result}, types::{PriceData, PriceSource}};
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
+use crate::{config::CommitmentPolicy, error::{OracleError, Result}, oracle_source::OracleSource, rpc_pool::RpcPool, types::{FeedStatus, PriceData, PriceSource}};
+use async_trait::async_trait;
+use solana_sdk::{hash::hash, pubkey::Pubkey};
+use switchboard_on_demand::PullFeedAccountData;
 use switchboard_v2::AggregatorAccountData;
 use rust_decimal::Decimal;
-use std::str::FromStr;
+use serde::Serialize;
+use std::{str::FromStr, sync::Arc, time::Duration};
 use tracing::{debug, warn};
 
+/// On-Demand feed values are fixed point with 18 decimals
+const PULL_FEED_DECIMALS: u32 = 18;
+
+/// Account layout of a Switchboard feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedKind {
+    /// `switchboard_v2::AggregatorAccountData` (push rounds, being sunset)
+    V2,
+    /// `switchboard_on_demand::PullFeedAccountData`
+    OnDemand,
+}
+
+/// A registered Switchboard feed
+#[derive(Debug, Clone, Copy)]
+struct SwitchboardFeed {
+    address: Pubkey,
+    kind: FeedKind,
+}
+
 /// Client for interacting with Switchboard network
+///
+/// Each symbol is either a v2 aggregator or an On-Demand pull feed; both
+/// decode to the same `PriceData` and pass the same quorum check.
+#[derive(Clone)]
 pub struct SwitchboardClient {
-    rpc_client: RpcClient,
-    aggregators: std::collections::HashMap<String, Pubkey>,
+    rpc_pool: Arc<RpcPool>,
+    aggregators: std::collections::HashMap<String, SwitchboardFeed>,
+    /// Upper bound on a single RPC request
+    timeout: Duration,
+    /// Commitment of account reads
+    commitment: CommitmentPolicy,
 }
 
 impl SwitchboardClient {
-    pub fn new(rpc_url: &str) -> Self {
+    /// Create a new client
+    ///
+    /// # Arguments
+    /// * `rpc_url` - Solana RPC endpoint
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn new(rpc_url: &str, timeout: Duration) -> Self {
+        Self::with_pool(Arc::new(RpcPool::single(rpc_url, timeout)), timeout)
+    }
+
+    /// Create a client reading through a shared RPC pool
+    ///
+    /// # Arguments
+    /// * `rpc_pool` - Health-scored Solana RPC endpoints
+    /// * `timeout` - Upper bound on a single RPC request
+    pub fn with_pool(rpc_pool: Arc<RpcPool>, timeout: Duration) -> Self {
         Self {
-            rpc_client: RpcClient::new(rpc_url.to_string()),
+            rpc_pool,
             aggregators: std::collections::HashMap::new(),
+            timeout,
+            commitment: CommitmentPolicy::default(),
         }
     }
 
+    /// Read accounts at the commitment `policy` gives this source
+    pub fn set_commitment(&mut self, policy: CommitmentPolicy) {
+        self.commitment = policy;
+    }
+
     pub fn register_aggregator(&mut self, symbol: String, aggregator_address: &str) -> Result<()> {
-        let pubkey = Pubkey::from_str(aggregator_address)
+        self.register(symbol, aggregator_address, FeedKind::V2)
+    }
+
+    /// Register an On-Demand pull feed for `symbol`, replacing any v2 aggregator
+    pub fn register_pull_feed(&mut self, symbol: String, feed_address: &str) -> Result<()> {
+        self.register(symbol, feed_address, FeedKind::OnDemand)
+    }
+
+    fn register(&mut self, symbol: String, address: &str, kind: FeedKind) -> Result<()> {
+        let pubkey = Pubkey::from_str(address)
             .map_err(|e| OracleError::ParseError(format!("Invalid pubkey: {}", e)))?;
         
-        self.aggregators.insert(symbol.clone(), pubkey);
-        debug!("Registered Switchboard aggregator for {}: {}", symbol, aggregator_address);
+        self.aggregators.insert(symbol.clone(), SwitchboardFeed { address: pubkey, kind });
+        debug!("Registered Switchboard {:?} feed for {}: {}", kind, symbol, address);
         
         Ok(())
     }
 
-    pub async fn get_price(&self, symbol: &str) -> Result<PriceData> {
-        // Step 1: Look up the aggregator address
-        let aggregator_address = self.aggregators
-            .get(symbol)
-            .ok_or_else(|| OracleError::NoPriceData(
-                format!("No Switchboard aggregator registered for {}", symbol)
-            ))?;
+    /// Address of the aggregator registered for `symbol`
+    pub fn aggregator_address(&self, symbol: &str) -> Option<Pubkey> {
+        self.aggregators.get(symbol).map(|feed| feed.address)
+    }
 
-        debug!("Fetching Switchboard price for {} from {}", symbol, aggregator_address);
+    fn feed(&self, symbol: &str) -> Result<SwitchboardFeed> {
+        self.aggregators.get(symbol).copied().ok_or_else(|| OracleError::NoPriceData(
+            format!("No Switchboard aggregator registered for {}", symbol)
+        ))
+    }
 
-        // Step 2: Read account data from Solana
-        let account_data = self.rpc_client
-            .get_account_data(aggregator_address)
-            .map_err(|e| OracleError::SolanaError(format!(
-                "Failed to fetch account: {}", e
-            )))?;
+    /// Decode account data of `kind` into a price for `symbol`
+    pub fn decode(symbol: &str, kind: FeedKind, account_data: &[u8]) -> Result<PriceData> {
+        match kind {
+            FeedKind::V2 => Self::decode_price(symbol, account_data),
+            FeedKind::OnDemand => Self::decode_pull_feed(symbol, account_data),
+        }
+    }
 
-        // Step 3: Parse Switchboard aggregator format
-        let aggregator = AggregatorAccountData::new(&account_data)
+    /// Parse raw aggregator account data into a price for `symbol`
+    ///
+    /// Shared by single reads and batched snapshots.
+    pub fn decode_price(symbol: &str, account_data: &[u8]) -> Result<PriceData> {
+        let aggregator = AggregatorAccountData::new_from_bytes(account_data)
             .map_err(|e| OracleError::ParseError(format!(
                 "Failed to parse Switchboard account: {:?}", e
             )))?;
 
-        // Step 4: Extract latest result
-        // Switchboard stores the result as a SwitchboardDecimal
-        let latest_result = aggregator.latest_confirmed_round.result
-            .ok_or_else(|| OracleError::NoPriceData(
-                format!("No confirmed round for {}", symbol)
-            ))?;
+        // A round that closed with fewer responses than the aggregator
+        // requires is not a valid result
+        let round = &aggregator.latest_confirmed_round;
+        if round.num_success < aggregator.min_oracle_results {
+            return Err(OracleError::InsufficientOracleQuorum(format!(
+                "{} round has {} of {} required oracle results",
+                symbol, round.num_success, aggregator.min_oracle_results
+            )));
+        }
 
-        // Convert SwitchboardDecimal to our Decimal type
-        let price = self.switchboard_decimal_to_decimal(&latest_result)?;
+        // Extract latest result
+        // Switchboard stores the result as a SwitchboardDecimal
+        let price = Self::switchboard_decimal_to_decimal(&round.result)?;
 
         // Calculate confidence from standard deviation
         // Switchboard provides std_deviation as a measure of oracle disagreement
-        let std_deviation = aggregator.latest_confirmed_round.std_deviation
-            .ok_or_else(|| OracleError::NoPriceData(
-                format!("No std deviation for {}", symbol)
-            ))?;
-        
-        let confidence = self.switchboard_decimal_to_decimal(&std_deviation)?;
+        let confidence = Self::switchboard_decimal_to_decimal(&round.std_deviation)?;
 
         // Get timestamp of the round
-        let timestamp = aggregator.latest_confirmed_round.round_open_timestamp;
+        let timestamp = round.round_open_timestamp;
 
-        // Step 5: Create and return price data
+        // Create and return price data
         let price_data = PriceData {
             symbol: symbol.to_string(),
             price,
             confidence,
             timestamp,
+            slot: Some(round.round_open_slot),
+            status: FeedStatus::Trading,
+            publishers: None,
             source: PriceSource::Switchboard,
         };
 
@@ -91,19 +158,69 @@ impl SwitchboardClient {
         Ok(price_data)
     }
 
-    pub async fn get_prices(&self, symbols: &[String]) -> Vec<Result<PriceData>> {
-        let mut results = Vec::new();
-        
-        for symbol in symbols {
-            results.push(self.get_price(symbol).await);
+    /// Parse raw On-Demand pull feed data into a price for `symbol`
+    ///
+    /// Same checks as v2: the result must come from at least
+    /// `min_responses` oracle samples. Timestamp and slot are those of the
+    /// last update, so the aggregator's age and slot-lag checks apply.
+    pub fn decode_pull_feed(symbol: &str, account_data: &[u8]) -> Result<PriceData> {
+        let feed = Self::load_pull_feed(account_data)?;
+        let result = &feed.result;
+
+        if (result.num_samples as u32) < feed.min_responses {
+            return Err(OracleError::InsufficientOracleQuorum(format!(
+                "{} result has {} of {} required oracle samples",
+                symbol, result.num_samples, feed.min_responses
+            )));
         }
-        
-        results
+
+        let to_decimal = |value: i128| Decimal::try_from_i128_with_scale(value, PULL_FEED_DECIMALS)
+            .map_err(|e| OracleError::ParseError(format!("Invalid pull feed value: {}", e)));
+
+        let price_data = PriceData {
+            symbol: symbol.to_string(),
+            price: to_decimal(result.value)?,
+            confidence: to_decimal(result.std_dev)?,
+            timestamp: feed.last_update_timestamp,
+            slot: Some(result.slot),
+            status: FeedStatus::Trading,
+            publishers: None,
+            source: PriceSource::Switchboard,
+        };
+
+        debug!("Switchboard On-Demand price for {}: ${} ±${}",
+               symbol, price_data.price, price_data.confidence);
+
+        Ok(price_data)
     }
 
+    /// Check the Anchor discriminator and copy out the account body
+    ///
+    /// The struct holds `i128`s (16-byte alignment) while account data
+    /// behind the 8-byte discriminator is only 8-byte aligned, so the body
+    /// is read unaligned rather than cast in place.
+    fn load_pull_feed(account_data: &[u8]) -> Result<PullFeedAccountData> {
+        let discriminator = &hash(b"account:PullFeedAccountData").to_bytes()[..8];
+        if account_data.get(..8) != Some(discriminator) {
+            return Err(OracleError::ParseError("Not a Switchboard pull feed account".to_string()));
+        }
+
+        let body = account_data.get(8..8 + std::mem::size_of::<PullFeedAccountData>())
+            .ok_or_else(|| OracleError::ParseError("Pull feed account too short".to_string()))?;
+
+        Ok(bytemuck::pod_read_unaligned(body))
+    }
+
+    /// Fetch raw account data without blocking the runtime
+    ///
+    /// The whole call, including the RPC client's own retries, is bounded
+    /// by `timeout`.
+    async fn get_account_data(&self, symbol: &str, address: &Pubkey) -> Result<Vec<u8>> {
+        let commitment = self.commitment.resolve(self.name(), symbol);
+        self.rpc_pool.get_account_data(address, commitment, self.timeout).await
+    }
 
     fn switchboard_decimal_to_decimal(
-        &self,
         sb_decimal: &switchboard_v2::SwitchboardDecimal
     ) -> Result<Decimal> {
         // Get the mantissa (the number without decimal point)
@@ -128,41 +245,124 @@ impl SwitchboardClient {
     /// Get detailed aggregator information
     /// 
     /// This provides metadata about the oracle aggregator:
-    /// - How many oracles are participating
+    /// - Queue and configuration (variance threshold, force-report period)
+    /// - Which oracles were asked and which responded
     /// - Last update time
-    /// - Configuration parameters
     ///
-    /// Useful for monitoring and debugging.
+    /// Useful for monitoring and debugging a lagging feed.
     ///
     /// # Example
     /// ```rust
     /// let info = client.get_aggregator_info("BTC/USD").await?;
-    /// println!("Oracles: {}", info.num_success);
+    /// println!("Oracles: {} of {}", info.num_success, info.num_oracles);
     /// ```
     pub async fn get_aggregator_info(&self, symbol: &str) -> Result<AggregatorInfo> {
-        let aggregator_address = self.aggregators
-            .get(symbol)
-            .ok_or_else(|| OracleError::NoPriceData(
-                format!("No aggregator for {}", symbol)
-            ))?;
-
-        let account_data = self.rpc_client
-            .get_account_data(aggregator_address)
-            .map_err(|e| OracleError::SolanaError(format!("{}", e)))?;
-
-        let aggregator = AggregatorAccountData::new(&account_data)
-            .map_err(|e| OracleError::ParseError(format!("{:?}", e)))?;
-
-        Ok(AggregatorInfo {
-            name: aggregator.name.iter()
-                .filter(|&&c| c != 0)
-                .map(|&c| c as char)
-                .collect(),
-            num_oracles: aggregator.oracle_request_batch_size as usize,
-            num_success: aggregator.latest_confirmed_round.num_success as usize,
-            min_responses: aggregator.min_oracle_results as usize,
-            last_update: aggregator.latest_confirmed_round.round_open_timestamp,
-        })
+        let feed = self.feed(symbol)?;
+        let account_data = self.get_account_data(symbol, &feed.address).await?;
+
+        Self::decode_aggregator_info(feed.kind, &account_data)
+    }
+
+    /// Parse account data of `kind` into `AggregatorInfo`
+    pub fn decode_aggregator_info(kind: FeedKind, account_data: &[u8]) -> Result<AggregatorInfo> {
+        let name = |bytes: &[u8]| bytes.iter()
+            .filter(|&&c| c != 0)
+            .map(|&c| c as char)
+            .collect();
+
+        match kind {
+            FeedKind::V2 => {
+                let aggregator = AggregatorAccountData::new_from_bytes(account_data)
+                    .map_err(|e| OracleError::ParseError(format!("{:?}", e)))?;
+                let round = &aggregator.latest_confirmed_round;
+
+                // The round's oracles, in assignment order
+                let batch_size = (aggregator.oracle_request_batch_size as usize)
+                    .min(round.oracle_pubkeys_data.len());
+                let oracles = (0..batch_size)
+                    .filter(|&i| round.oracle_pubkeys_data[i] != Pubkey::default())
+                    .map(|i| OracleResponse {
+                        oracle: round.oracle_pubkeys_data[i].to_string(),
+                        responded: round.medians_fulfilled[i],
+                        errored: round.errors_fulfilled[i],
+                        slot: None,
+                    })
+                    .collect();
+
+                Ok(AggregatorInfo {
+                    name: name(&aggregator.name),
+                    kind,
+                    queue: aggregator.queue_pubkey.to_string(),
+                    num_oracles: aggregator.oracle_request_batch_size as usize,
+                    num_success: round.num_success as usize,
+                    min_responses: aggregator.min_oracle_results as usize,
+                    variance_threshold: Self::switchboard_decimal_to_decimal(&aggregator.variance_threshold)?,
+                    force_report_period: Some(aggregator.force_report_period),
+                    last_update: round.round_open_timestamp,
+                    oracles,
+                })
+            }
+            FeedKind::OnDemand => {
+                let pull_feed = Self::load_pull_feed(account_data)?;
+                let result = &pull_feed.result;
+
+                // Submissions within the feed's staleness window of the
+                // current result count as responses to it
+                let oracles = pull_feed.submissions.iter()
+                    .filter(|submission| submission.oracle != Pubkey::default())
+                    .map(|submission| OracleResponse {
+                        oracle: submission.oracle.to_string(),
+                        responded: submission.slot + pull_feed.max_staleness as u64 >= result.slot,
+                        errored: false,
+                        slot: Some(submission.slot),
+                    })
+                    .collect();
+
+                Ok(AggregatorInfo {
+                    name: name(&pull_feed.name),
+                    kind,
+                    queue: pull_feed.queue.to_string(),
+                    // Pull feeds sample any oracle on the queue
+                    num_oracles: result.num_samples as usize,
+                    num_success: result.num_samples as usize,
+                    min_responses: pull_feed.min_responses as usize,
+                    // Stored scaled by 1e9
+                    variance_threshold: Decimal::from_i128_with_scale(pull_feed.max_variance as i128, 9),
+                    // Pull feeds only update when someone pays for an update
+                    force_report_period: None,
+                    last_update: pull_feed.last_update_timestamp,
+                    oracles,
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl OracleSource for SwitchboardClient {
+    fn name(&self) -> &'static str {
+        "Switchboard"
+    }
+
+    fn source(&self) -> PriceSource {
+        PriceSource::Switchboard
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        self.aggregators.keys().cloned().collect()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        // Step 1: Look up the aggregator address
+        let feed = self.feed(symbol)?;
+
+        debug!("Fetching Switchboard price for {} from {}", symbol, feed.address);
+
+        // Step 2: Read account data from Solana
+        let account_data = self.get_account_data(symbol, &feed.address).await?;
+
+        // Step 3: Parse the v2 or On-Demand format
+        Self::decode(symbol, feed.kind, &account_data)
     }
 
     /// Check if Switchboard service is healthy
@@ -174,7 +374,7 @@ impl SwitchboardClient {
     ///
     /// # Returns
     /// `true` if healthy, `false` otherwise
-    pub async fn health_check(&self) -> bool {
+    async fn health_check(&self) -> bool {
         if let Some((symbol, _)) = self.aggregators.iter().next() {
             match self.get_price(symbol).await {
                 Ok(price_data) => {
@@ -204,6 +404,14 @@ impl SwitchboardClient {
             false
         }
     }
+
+    fn account_address(&self, symbol: &str) -> Option<Pubkey> {
+        self.aggregator_address(symbol)
+    }
+
+    fn decode_account(&self, symbol: &str, data: &[u8]) -> Result<PriceData> {
+        Self::decode(symbol, self.feed(symbol)?.kind, data)
+    }
 }
 
 // ============================================================================
@@ -211,11 +419,17 @@ impl SwitchboardClient {
 // ============================================================================
 
 /// Information about a Switchboard aggregator
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AggregatorInfo {
     /// Human-readable name of the aggregator
     pub name: String,
     
+    /// v2 aggregator or On-Demand pull feed
+    pub kind: FeedKind,
+    
+    /// Oracle queue the feed draws oracles from
+    pub queue: String,
+    
     /// Total number of oracles configured
     pub num_oracles: usize,
     
@@ -225,8 +439,35 @@ pub struct AggregatorInfo {
     /// Minimum responses required for valid result
     pub min_responses: usize,
     
+    /// Change that triggers a new round (v2) or largest accepted spread
+    /// of samples (On-Demand)
+    pub variance_threshold: Decimal,
+    
+    /// Seconds after which a v2 round is forced regardless of variance;
+    /// `None` for pull feeds, which only update on request
+    pub force_report_period: Option<i64>,
+    
     /// Timestamp of last update
     pub last_update: i64,
+    
+    /// Oracles of the last round (v2) or latest submissions (On-Demand)
+    pub oracles: Vec<OracleResponse>,
+}
+
+/// One oracle's part in the latest result
+#[derive(Debug, Clone, Serialize)]
+pub struct OracleResponse {
+    /// Oracle account address
+    pub oracle: String,
+    
+    /// Whether its value went into the latest result
+    pub responded: bool,
+    
+    /// Whether it reported an error instead (v2 only)
+    pub errored: bool,
+    
+    /// Slot of its latest submission (On-Demand only)
+    pub slot: Option<u64>,
 }
 
 #[cfg(test)]
@@ -234,16 +475,129 @@ mod tests {
     use super::*;
     use switchboard_v2::SwitchboardDecimal;
 
+    /// Serialize a Switchboard v2 aggregator account with one confirmed round
+    fn switchboard_account_bytes(result: f64, num_success: u32, min_oracle_results: u32) -> Vec<u8> {
+        let mut aggregator: AggregatorAccountData = bytemuck::Zeroable::zeroed();
+        aggregator.min_oracle_results = min_oracle_results;
+        aggregator.latest_confirmed_round.num_success = num_success;
+        aggregator.latest_confirmed_round.result = SwitchboardDecimal::from_f64(result);
+        aggregator.latest_confirmed_round.std_deviation = SwitchboardDecimal::from_f64(5.0);
+        aggregator.latest_confirmed_round.round_open_timestamp = 1_702_234_567;
+        aggregator.latest_confirmed_round.round_open_slot = 310_000_000;
+
+        let discriminator = &hash(b"account:AggregatorAccountData").to_bytes()[..8];
+        [discriminator, bytemuck::bytes_of(&aggregator)].concat()
+    }
+
+    #[test]
+    fn test_round_quorum() {
+        let price = SwitchboardClient::decode_price("BTC/USD", &switchboard_account_bytes(50_000.0, 3, 3)).unwrap();
+        assert_eq!(price.price, Decimal::from(50_000));
+        assert_eq!(price.timestamp, 1_702_234_567);
+        assert_eq!(price.slot, Some(310_000_000));
+        assert_eq!(price.status, FeedStatus::Trading);
+
+        assert!(matches!(
+            SwitchboardClient::decode_price("BTC/USD", &switchboard_account_bytes(50_000.0, 2, 3)),
+            Err(OracleError::InsufficientOracleQuorum(_))
+        ));
+    }
+
+    /// Serialize an On-Demand pull feed with `num_samples` of `min_responses`
+    fn pull_feed_bytes(value: i128, num_samples: u8, min_responses: u32) -> Vec<u8> {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.min_responses = min_responses;
+        feed.last_update_timestamp = 1_702_234_567;
+        feed.result.value = value;
+        feed.result.std_dev = 5 * 10_i128.pow(PULL_FEED_DECIMALS);
+        feed.result.num_samples = num_samples;
+        feed.result.slot = 310_000_000;
+
+        let discriminator = &hash(b"account:PullFeedAccountData").to_bytes()[..8];
+        [discriminator, bytemuck::bytes_of(&feed)].concat()
+    }
+
+    #[test]
+    fn test_pull_feed() {
+        let data = pull_feed_bytes(50_000 * 10_i128.pow(PULL_FEED_DECIMALS), 3, 3);
+
+        let price = SwitchboardClient::decode("BTC/USD", FeedKind::OnDemand, &data).unwrap();
+        assert_eq!(price.price, Decimal::from(50_000));
+        assert_eq!(price.confidence, Decimal::from(5));
+        assert_eq!(price.timestamp, 1_702_234_567);
+        assert_eq!(price.slot, Some(310_000_000));
+        assert_eq!(price.source, PriceSource::Switchboard);
+
+        assert!(matches!(
+            SwitchboardClient::decode_pull_feed("BTC/USD", &pull_feed_bytes(50_000, 1, 3)),
+            Err(OracleError::InsufficientOracleQuorum(_))
+        ));
+
+        // Account data isn't 16-byte aligned behind its discriminator
+        let misaligned = [&[0u8][..], &data].concat();
+        let price = SwitchboardClient::decode_pull_feed("BTC/USD", &misaligned[1..]).unwrap();
+        assert_eq!(price.price, Decimal::from(50_000));
+
+        // A v2 account isn't a pull feed
+        assert!(matches!(
+            SwitchboardClient::decode("BTC/USD", FeedKind::OnDemand, &switchboard_account_bytes(50_000.0, 3, 3)),
+            Err(OracleError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_aggregator_info() {
+        let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let queue = Pubkey::new_unique();
+
+        let mut aggregator: AggregatorAccountData = bytemuck::Zeroable::zeroed();
+        aggregator.queue_pubkey = queue;
+        aggregator.oracle_request_batch_size = 3;
+        aggregator.min_oracle_results = 2;
+        aggregator.force_report_period = 3_600;
+        aggregator.variance_threshold = SwitchboardDecimal { mantissa: 5, scale: 1 };
+        aggregator.latest_confirmed_round.num_success = 2;
+        aggregator.latest_confirmed_round.oracle_pubkeys_data[..3].copy_from_slice(&oracles);
+        aggregator.latest_confirmed_round.medians_fulfilled[..2].copy_from_slice(&[true, true]);
+        aggregator.latest_confirmed_round.errors_fulfilled[2] = true;
+        let discriminator = &hash(b"account:AggregatorAccountData").to_bytes()[..8];
+        let data = [discriminator, bytemuck::bytes_of(&aggregator)].concat();
+
+        let info = SwitchboardClient::decode_aggregator_info(FeedKind::V2, &data).unwrap();
+        assert_eq!(info.queue, queue.to_string());
+        assert_eq!(info.variance_threshold, Decimal::new(5, 1));
+        assert_eq!(info.force_report_period, Some(3_600));
+        assert_eq!(info.oracles.len(), 3);
+        assert!(info.oracles[0].responded && info.oracles[1].responded);
+        assert!(!info.oracles[2].responded && info.oracles[2].errored);
+        assert_eq!(info.oracles[2].oracle, oracles[2].to_string());
+
+        // Pull feeds have no force-report period
+        let info = SwitchboardClient::decode_aggregator_info(FeedKind::OnDemand, &pull_feed_bytes(50_000, 3, 3)).unwrap();
+        assert_eq!(info.kind, FeedKind::OnDemand);
+        assert_eq!(info.force_report_period, None);
+        assert!(info.oracles.is_empty());
+    }
+
+    #[test]
+    fn test_feed_kind_per_symbol() {
+        let mut client = SwitchboardClient::new("http://localhost", Duration::from_secs(5));
+        client.register_aggregator("BTC/USD".to_string(), "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee").unwrap();
+        client.register_pull_feed("ETH/USD".to_string(), "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR").unwrap();
+
+        let pull_feed = pull_feed_bytes(3_000 * 10_i128.pow(PULL_FEED_DECIMALS), 2, 1);
+        assert_eq!(client.decode_account("ETH/USD", &pull_feed).unwrap().price, Decimal::from(3_000));
+        assert!(client.decode_account("BTC/USD", &pull_feed).is_err());
+    }
+
     #[test]
     fn test_switchboard_decimal_conversion() {
-        let client = SwitchboardClient::new("http://localhost");
-        
         // Test case 1: Basic conversion
         let sb_decimal = SwitchboardDecimal {
             mantissa: 5000000000,
             scale: 5,
         };
-        let result = client.switchboard_decimal_to_decimal(&sb_decimal).unwrap();
+        let result = SwitchboardClient::switchboard_decimal_to_decimal(&sb_decimal).unwrap();
         assert_eq!(result, Decimal::from(50000));
         
         // Test case 2: No scale
@@ -251,7 +605,7 @@ mod tests {
             mantissa: 50000,
             scale: 0,
         };
-        let result = client.switchboard_decimal_to_decimal(&sb_decimal).unwrap();
+        let result = SwitchboardClient::switchboard_decimal_to_decimal(&sb_decimal).unwrap();
         assert_eq!(result, Decimal::from(50000));
         
         // Test case 3: High precision
@@ -259,7 +613,7 @@ mod tests {
             mantissa: 5000099999,
             scale: 5,
         };
-        let result = client.switchboard_decimal_to_decimal(&sb_decimal).unwrap();
+        let result = SwitchboardClient::switchboard_decimal_to_decimal(&sb_decimal).unwrap();
         // 5000099999 / 100000 = 50000.99999
         assert_eq!(result.to_string(), "50000.99999");
     }
@@ -1,5 +1,33 @@
+use crate::error::{OracleError, Result};
+use oracle_consensus::Quote;
 use serde::{Deserialize, Serialize};
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+/// Exponent of the fixed-point prices shared with the on-chain program
+/// (8 decimals, matching the DECIMAL(20, 8) columns)
+pub const FIXED_POINT_EXPO: i32 = -8;
+
+/// `value × 10^expo` as a `Decimal`
+///
+/// Exponents come from account data or the network, so nothing here may
+/// overflow or panic.
+///
+/// # Errors
+/// * `ParseError` - The exponent or the result is out of `Decimal` range
+pub fn scale_by_exponent(value: i128, expo: i32) -> Result<Decimal> {
+    let out_of_range = || OracleError::ParseError(format!("{}e{} is out of range", value, expo));
+
+    if expo <= 0 {
+        return Decimal::try_from_i128_with_scale(value, expo.unsigned_abs())
+            .map_err(|_| out_of_range());
+    }
+
+    let multiplier = 10_i64.checked_pow(expo as u32).ok_or_else(out_of_range)?;
+    Decimal::try_from_i128_with_scale(value, 0)
+        .ok()
+        .and_then(|value| value.checked_mul(Decimal::from(multiplier)))
+        .ok_or_else(out_of_range)
+}
 
 /// Represents a price from any oracle source
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,21 +41,146 @@ pub struct PriceData {
     /// Confidence interval (± value)
     pub confidence: Decimal,
     
-    /// Unix timestamp
+    /// Unix time the price was published
     pub timestamp: i64,
     
+    /// Solana slot the price was published at, for sources that have one
+    ///
+    /// Off-chain sources (and Hermes, whose slots are Pythnet's) leave this
+    /// empty and are only checked against `timestamp`.
+    #[serde(default)]
+    pub slot: Option<u64>,
+    
+    /// Trading status the source reported for the price
+    #[serde(default)]
+    pub status: FeedStatus,
+    
+    /// Publisher breakdown behind the price, when the source decodes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publishers: Option<PublisherStats>,
+    
     /// Oracle source
     pub source: PriceSource,
 }
 
+impl PriceData {
+    /// Convert to the shared fixed-point format (rounded to 8 decimals)
+    pub fn to_quote(&self) -> Result<Quote> {
+        let scale = Decimal::from(10_i64.pow((-FIXED_POINT_EXPO) as u32));
+        let price = (self.price * scale)
+            .round()
+            .to_i64()
+            .ok_or_else(|| OracleError::ParseError(format!("Price {} overflows i64", self.price)))?;
+        let confidence = (self.confidence * scale)
+            .round()
+            .to_u64()
+            .ok_or_else(|| OracleError::ParseError(format!("Invalid confidence {}", self.confidence)))?;
+
+        Ok(Quote {
+            price,
+            confidence,
+            expo: FIXED_POINT_EXPO,
+            timestamp: self.timestamp,
+        })
+    }
+
+    /// Convert a fixed-point quote back into a price for `symbol`
+    ///
+    /// # Errors
+    /// * `ParseError` - The quote's exponent puts it out of `Decimal` range
+    pub fn from_quote(symbol: &str, quote: &Quote, source: PriceSource) -> Result<Self> {
+        Ok(PriceData {
+            symbol: symbol.to_string(),
+            price: scale_by_exponent(quote.price as i128, quote.expo)?,
+            confidence: scale_by_exponent(quote.confidence as i128, quote.expo)?,
+            timestamp: quote.timestamp,
+            slot: None,
+            status: FeedStatus::Trading,
+            publishers: None,
+            source,
+        })
+    }
+}
+
 /// Oracle source identifier
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum PriceSource {
     Pyth,
     Switchboard,
+    /// Pyth prices read from the Hermes HTTP API
+    Hermes,
+    /// Reference price from centralized-exchange order books
+    Cex,
+    /// Spot price of an on-chain AMM pool
+    Amm,
     Aggregate,
 }
 
+/// Trading status of a feed, as reported by its source
+///
+/// Mirrors Pyth's `PriceStatus`. Clients return non-trading prices tagged
+/// with their status; the aggregator only lets `Trading` prices vote and
+/// reports `OracleError::PythNotTrading` when nothing else is left.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// Price is live and usable
+    #[default]
+    Trading,
+    /// Market is closed or trading was halted
+    Halted,
+    /// Price is being discovered (e.g. opening auction)
+    Auction,
+    /// Publishers chose not to quote
+    Ignored,
+    /// Not enough publishers to compute an aggregate
+    Unknown,
+}
+
+impl From<pyth_sdk_solana::state::PriceStatus> for FeedStatus {
+    fn from(status: pyth_sdk_solana::state::PriceStatus) -> Self {
+        use pyth_sdk_solana::state::PriceStatus;
+
+        match status {
+            PriceStatus::Trading => FeedStatus::Trading,
+            PriceStatus::Halted => FeedStatus::Halted,
+            PriceStatus::Auction => FeedStatus::Auction,
+            PriceStatus::Ignored => FeedStatus::Ignored,
+            PriceStatus::Unknown => FeedStatus::Unknown,
+        }
+    }
+}
+
+/// Publishers behind an aggregate price
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PublisherStats {
+    /// Publishers whose component price is trading and recent
+    pub live: u32,
+    
+    /// Publishers permissioned on the feed
+    pub total: u32,
+    
+    /// Mean absolute distance of live component prices from the aggregate
+    pub dispersion: Decimal,
+}
+
+/// Fetch counters of one source, kept by the aggregator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceFetchStats {
+    pub requests: u64,
+    
+    /// Failed requests, including timeouts
+    pub failures: u64,
+    
+    /// Requests that missed the per-source deadline
+    pub timeouts: u64,
+    
+    /// Latency of the latest request (ms)
+    pub last_latency_ms: u64,
+    
+    /// Unix timestamp of the latest successful request
+    pub last_success: Option<i64>,
+}
+
 /// Health status of an oracle source
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OracleHealth {
@@ -35,4 +188,36 @@ pub struct OracleHealth {
     pub is_healthy: bool,
     pub last_update: i64,
     pub error_count: u32,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_by_exponent() {
+        assert_eq!(scale_by_exponent(5_000_012_345_678, -8).unwrap(), Decimal::new(5_000_012_345_678, 8));
+        assert_eq!(scale_by_exponent(5, 3).unwrap(), Decimal::from(5_000));
+
+        // 10^19 overflows i64; i32::MIN can't be negated
+        for expo in [19, 40, i32::MAX, -29, i32::MIN] {
+            assert!(matches!(scale_by_exponent(1, expo), Err(OracleError::ParseError(_))), "expo {}", expo);
+        }
+        // In range exponent, out of range product
+        assert!(matches!(scale_by_exponent(i64::MAX as i128, 18), Err(OracleError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_from_quote_large_exponent() {
+        let quote = Quote { price: 5, confidence: 1, expo: 19, timestamp: 0 };
+        assert!(matches!(
+            PriceData::from_quote("BTC/USD", &quote, PriceSource::Aggregate),
+            Err(OracleError::ParseError(_))
+        ));
+
+        let quote = Quote { price: 5_000_000_000_000, confidence: 100_000_000, expo: FIXED_POINT_EXPO, timestamp: 0 };
+        let price = PriceData::from_quote("BTC/USD", &quote, PriceSource::Aggregate).unwrap();
+        assert_eq!(price.price, Decimal::from(50_000));
+        assert_eq!(price.confidence, Decimal::ONE);
+    }
+}
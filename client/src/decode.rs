@@ -0,0 +1,89 @@
+//! Account and return-data decoders
+//!
+//! Instructions like `validate_price_consensus` return their result through
+//! `set_return_data`, so callers read it from a simulation's return data
+//! rather than from an account.
+
+use crate::{ClientError, Result};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AnchorDeserialize};
+use oracle_price_feed::{MarketStatus, OracleConfig, PriceData, PublisherPrice};
+
+/// Decode an `OracleConfig` account (checks the discriminator)
+pub fn decode_oracle_config(data: &[u8]) -> Result<OracleConfig> {
+    OracleConfig::try_deserialize(&mut &data[..])
+        .map_err(|e| ClientError::AccountDecode(format!("OracleConfig: {}", e)))
+}
+
+/// Decode a keeper `PublisherPrice` account (checks the discriminator)
+pub fn decode_publisher_price(data: &[u8]) -> Result<PublisherPrice> {
+    PublisherPrice::try_deserialize(&mut &data[..])
+        .map_err(|e| ClientError::AccountDecode(format!("PublisherPrice: {}", e)))
+}
+
+/// Decode the `PriceData` returned by `get_pyth_price`,
+/// `validate_price_consensus` or `verify_price_attestation`
+pub fn decode_price_data(return_data: &[u8]) -> Result<PriceData> {
+    PriceData::try_from_slice(return_data)
+        .map_err(|e| ClientError::ReturnDataDecode(format!("PriceData: {}", e)))
+}
+
+/// Decode the `MarketStatus` returned by `get_market_status`
+pub fn decode_market_status(return_data: &[u8]) -> Result<MarketStatus> {
+    MarketStatus::try_from_slice(return_data)
+        .map_err(|e| ClientError::ReturnDataDecode(format!("MarketStatus: {}", e)))
+}
+
+/// Check that return data was set by the oracle program, then decode it
+///
+/// A CPI-ing program later in the transaction can overwrite return data,
+/// so the emitting program id must be verified before trusting it.
+pub fn decode_price_data_from(program_id: &Pubkey, return_data: &[u8]) -> Result<PriceData> {
+    if *program_id != oracle_price_feed::ID {
+        return Err(ClientError::UnexpectedProgram(program_id.to_string()));
+    }
+
+    decode_price_data(return_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use oracle_price_feed::PriceSource;
+
+    #[test]
+    fn test_price_data_roundtrip() {
+        let price = PriceData {
+            price: 5_000_012_345_678,
+            confidence: 2_500_000,
+            expo: -8,
+            timestamp: 1_702_234_567,
+            source: PriceSource::Internal,
+        };
+
+        let bytes = price.try_to_vec().unwrap();
+        let decoded = decode_price_data_from(&oracle_price_feed::ID, &bytes).unwrap();
+
+        assert_eq!(decoded.price, price.price);
+        assert_eq!(decoded.confidence, price.confidence);
+        assert_eq!(decoded.expo, price.expo);
+        assert_eq!(decoded.timestamp, price.timestamp);
+        assert_eq!(decoded.source, PriceSource::Internal);
+    }
+
+    #[test]
+    fn test_rejects_foreign_return_data() {
+        let bytes = PriceData {
+            price: 1,
+            confidence: 0,
+            expo: 0,
+            timestamp: 0,
+            source: PriceSource::Pyth,
+        }
+        .try_to_vec()
+        .unwrap();
+
+        let result = decode_price_data_from(&Pubkey::new_unique(), &bytes);
+        assert!(matches!(result, Err(ClientError::UnexpectedProgram(_))));
+    }
+}
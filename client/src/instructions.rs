@@ -0,0 +1,240 @@
+//! Instruction builders
+//!
+//! Each builder fills in the account list (including PDAs) and the Anchor
+//! instruction data for one program instruction.
+
+use crate::pda::{find_config_address, find_publisher_price_address};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use oracle_price_feed::{accounts, instruction, PriceAttestation, PriceData, TradingSchedule};
+
+/// Risk parameters for `initialize_config`
+#[derive(Debug, Clone)]
+pub struct ConfigParams {
+    pub pyth_feed: Pubkey,
+    pub switchboard_aggregator: Pubkey,
+    /// Max age in seconds
+    pub max_staleness: i64,
+    /// Max confidence interval in bps
+    pub max_confidence: u64,
+    /// Max deviation from median in bps
+    pub max_deviation: u64,
+    /// Lowest sane price, at `expo`
+    pub min_price: i64,
+    /// Highest sane price, at `expo`
+    pub max_price: i64,
+    /// Exponent every price for the symbol must be quoted at
+    pub expo: i32,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: oracle_price_feed::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Build `initialize_config` for a new symbol
+pub fn initialize_config(authority: Pubkey, symbol: &str, params: ConfigParams) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            config: find_config_address(symbol).0,
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            symbol: symbol.to_string(),
+            pyth_feed: params.pyth_feed,
+            switchboard_aggregator: params.switchboard_aggregator,
+            max_staleness: params.max_staleness,
+            max_confidence: params.max_confidence,
+            max_deviation: params.max_deviation,
+            min_price: params.min_price,
+            max_price: params.max_price,
+            expo: params.expo,
+        },
+    )
+}
+
+/// Build `migrate_config` for a symbol whose config predates appended fields
+pub fn migrate_config(authority: Pubkey, symbol: &str, expo: i32) -> Instruction {
+    build(
+        accounts::MigrateConfig {
+            config: find_config_address(symbol).0,
+            authority,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig { expo },
+    )
+}
+
+/// Build `get_pyth_price` reading `price_feed`
+pub fn get_pyth_price(symbol: &str, price_feed: Pubkey) -> Instruction {
+    build(
+        accounts::GetPythPrice {
+            price_feed,
+            config: find_config_address(symbol).0,
+        },
+        instruction::GetPythPrice {
+            symbol: symbol.to_string(),
+        },
+    )
+}
+
+/// Build `validate_price_consensus`
+///
+/// # Arguments
+/// * `prices` - Oracle prices to validate (all in the same exponent)
+/// * `include_publisher_price` - Also pass the symbol's keeper price account
+pub fn validate_price_consensus(
+    symbol: &str,
+    prices: Vec<PriceData>,
+    include_publisher_price: bool,
+) -> Instruction {
+    build(
+        accounts::ValidatePrice {
+            config: find_config_address(symbol).0,
+            publisher_price: include_publisher_price
+                .then(|| find_publisher_price_address(symbol).0),
+        },
+        instruction::ValidatePriceConsensus { prices },
+    )
+}
+
+/// Build `set_price_bounds`
+pub fn set_price_bounds(authority: Pubkey, symbol: &str, min_price: i64, max_price: i64) -> Instruction {
+    build(
+        accounts::SetPriceBounds {
+            config: find_config_address(symbol).0,
+            authority,
+        },
+        instruction::SetPriceBounds { min_price, max_price },
+    )
+}
+
+/// Build `set_trading_schedule`
+pub fn set_trading_schedule(authority: Pubkey, symbol: &str, schedule: TradingSchedule) -> Instruction {
+    build(
+        accounts::SetTradingSchedule {
+            config: find_config_address(symbol).0,
+            authority,
+        },
+        instruction::SetTradingSchedule { schedule },
+    )
+}
+
+/// Build `get_market_status`
+pub fn get_market_status(symbol: &str) -> Instruction {
+    build(
+        accounts::GetMarketStatus {
+            config: find_config_address(symbol).0,
+        },
+        instruction::GetMarketStatus {},
+    )
+}
+
+/// Build `set_allow_negative_prices`
+pub fn set_allow_negative_prices(authority: Pubkey, symbol: &str, allow_negative_prices: bool) -> Instruction {
+    build(
+        accounts::SetAllowNegativePrices {
+            config: find_config_address(symbol).0,
+            authority,
+        },
+        instruction::SetAllowNegativePrices { allow_negative_prices },
+    )
+}
+
+/// Build `set_publishers`
+pub fn set_publishers(authority: Pubkey, symbol: &str, publishers: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::SetPublishers {
+            config: find_config_address(symbol).0,
+            authority,
+        },
+        instruction::SetPublishers { publishers },
+    )
+}
+
+/// Build `submit_price` signed by a whitelisted keeper
+pub fn submit_price(
+    publisher: Pubkey,
+    symbol: &str,
+    price: i64,
+    confidence: u64,
+    expo: i32,
+) -> Instruction {
+    build(
+        accounts::SubmitPrice {
+            config: find_config_address(symbol).0,
+            publisher_price: find_publisher_price_address(symbol).0,
+            publisher,
+            system_program: system_program::ID,
+        },
+        instruction::SubmitPrice {
+            symbol: symbol.to_string(),
+            price,
+            confidence,
+            expo,
+        },
+    )
+}
+
+/// Build `set_attestation_signers`
+pub fn set_attestation_signers(
+    authority: Pubkey,
+    symbol: &str,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    build(
+        accounts::SetAttestationSigners {
+            config: find_config_address(symbol).0,
+            authority,
+        },
+        instruction::SetAttestationSigners { signers, threshold },
+    )
+}
+
+/// Build `verify_price_attestation`
+///
+/// The Ed25519 sig-verify instructions for `attestation.message()` must be
+/// placed before this instruction in the same transaction.
+pub fn verify_price_attestation(attestation: PriceAttestation) -> Instruction {
+    build(
+        accounts::VerifyPriceAttestation {
+            config: find_config_address(&attestation.symbol).0,
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::VerifyPriceAttestation { attestation },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_price_feed::PriceSource;
+
+    #[test]
+    fn test_validate_accounts() {
+        let prices = vec![PriceData {
+            price: 5_000_000_000_000,
+            confidence: 1_000_000,
+            expo: -8,
+            timestamp: 0,
+            source: PriceSource::Pyth,
+        }];
+
+        // Without publisher account, the optional slot holds the program id
+        let ix = validate_price_consensus("BTC/USD", prices.clone(), false);
+        assert_eq!(ix.accounts.len(), 2);
+        assert_eq!(ix.accounts[0].pubkey, find_config_address("BTC/USD").0);
+        assert_eq!(ix.accounts[1].pubkey, oracle_price_feed::ID);
+
+        let ix = validate_price_consensus("BTC/USD", prices, true);
+        assert_eq!(ix.accounts[1].pubkey, find_publisher_price_address("BTC/USD").0);
+    }
+}
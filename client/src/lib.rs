@@ -0,0 +1,46 @@
+//! Oracle Price Feed Client
+//!
+//! Typed helpers for talking to the `oracle_price_feed` program from Rust
+//! services, so nobody has to hand-assemble account lists or instruction
+//! data.
+//!
+//! # What's here
+//! - `pda`: addresses derived from the `oracle-config` / `publisher-price` seeds
+//! - `instructions`: one builder per program instruction
+//! - `decode`: account decoders and return-data decoding
+//!
+//! # Example
+//! ```rust,ignore
+//! let ix = oracle_price_feed_client::instructions::validate_price_consensus(
+//!     "BTC/USD",
+//!     prices,
+//!     false,
+//! );
+//! // simulate `ix`, then:
+//! let consensus = oracle_price_feed_client::decode::decode_price_data(&return_data)?;
+//! ```
+
+pub mod decode;
+pub mod instructions;
+pub mod pda;
+
+pub use oracle_price_feed::{
+    HolidayOverride, MarketStatus, OracleConfig, PriceAttestation, PriceData, PriceSource,
+    PublisherPrice, SessionWindow, TradingSchedule, ID as PROGRAM_ID,
+};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Failed to decode account: {0}")]
+    AccountDecode(String),
+
+    #[error("Failed to decode return data: {0}")]
+    ReturnDataDecode(String),
+
+    #[error("Return data came from unexpected program: {0}")]
+    UnexpectedProgram(String),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
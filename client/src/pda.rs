@@ -0,0 +1,49 @@
+//! Program-derived addresses used by the oracle program
+
+use anchor_lang::prelude::Pubkey;
+
+/// Seed prefix of the per-symbol `OracleConfig` account
+pub const ORACLE_CONFIG_SEED: &[u8] = b"oracle-config";
+
+/// Seed prefix of the per-symbol keeper `PublisherPrice` account
+pub const PUBLISHER_PRICE_SEED: &[u8] = b"publisher-price";
+
+/// Derive the `OracleConfig` address for a symbol
+///
+/// # Example
+/// ```rust,ignore
+/// let (config, bump) = find_config_address("BTC/USD");
+/// ```
+pub fn find_config_address(symbol: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORACLE_CONFIG_SEED, symbol.as_bytes()],
+        &oracle_price_feed::ID,
+    )
+}
+
+/// Derive the keeper `PublisherPrice` address for a symbol
+pub fn find_publisher_price_address(symbol: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PUBLISHER_PRICE_SEED, symbol.as_bytes()],
+        &oracle_price_feed::ID,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addresses_are_per_symbol() {
+        let (btc, _) = find_config_address("BTC/USD");
+        let (eth, _) = find_config_address("ETH/USD");
+        assert_ne!(btc, eth);
+
+        // Same symbol, different seed prefix → different account
+        let (btc_publisher, _) = find_publisher_price_address("BTC/USD");
+        assert_ne!(btc, btc_publisher);
+
+        // Derivation is deterministic
+        assert_eq!(find_config_address("BTC/USD").0, btc);
+    }
+}
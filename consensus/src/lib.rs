@@ -0,0 +1,353 @@
+//! Oracle Consensus Math
+//!
+//! The single implementation of price validation and consensus, shared by
+//! the on-chain program and the backend. The backend drops failing quotes
+//! one by one where the program rejects the whole set, then runs
+//! `validate_consensus` on the exact set it serves from, so the program
+//! accepts every set behind a served price. With the default median
+//! strategy the served price is also the program's; other backend
+//! aggregation strategies may serve a different price from that set.
+//!
+//! # Number format
+//! Prices are fixed-point: `price × 10^expo`, exactly like Pyth. All math is
+//! integer-only (`i128`/`u128` intermediates), so results are identical on
+//! BPF and x86. The backend converts its `Decimal`s to this format first.
+//!
+//! # Pipeline
+//! ```text
+//! quotes → check_quote (sign, staleness, confidence) → median → check_deviation → consensus
+//! ```
+//!
+//! # Zero and negative prices
+//! - Zero is never a valid price: every relative metric (confidence and
+//!   deviation in bps) divides by it. It fails with `ZeroPrice`, never
+//!   "0 bps".
+//! - Negative prices are rejected with `NegativePrice` unless the market is
+//!   signed (`allow_negative`), e.g. spreads and basis. Ratios then use the
+//!   magnitude `|price|`.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// One price observation in fixed-point form
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    /// Price in units of 10^expo
+    pub price: i64,
+    /// Confidence interval (±) in units of 10^expo
+    pub confidence: u64,
+    /// Price exponent
+    pub expo: i32,
+    /// Unix timestamp the price was published
+    pub timestamp: i64,
+}
+
+/// Risk limits applied to every quote
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationParams {
+    /// Max age in seconds (inclusive)
+    pub max_staleness: i64,
+    /// Max confidence interval in bps (inclusive)
+    pub max_confidence_bps: u64,
+    /// Max deviation from the median in bps (inclusive)
+    pub max_deviation_bps: u64,
+    /// Accept negative prices (spread / basis markets)
+    pub allow_negative: bool,
+}
+
+/// Result of a median calculation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Median {
+    pub quote: Quote,
+    /// Index of the input quote used as-is (odd count), `None` when the
+    /// median averages the two middle quotes (even count)
+    pub index: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsensusError {
+    NoPriceData,
+    StalePriceData,
+    ConfidenceTooLarge,
+    PriceDeviationTooLarge,
+    ExponentMismatch,
+    MathOverflow,
+    ZeroPrice,
+    NegativePrice,
+}
+
+impl fmt::Display for ConsensusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ConsensusError::NoPriceData => "no price data",
+            ConsensusError::StalePriceData => "price data is stale",
+            ConsensusError::ConfidenceTooLarge => "confidence interval too large",
+            ConsensusError::PriceDeviationTooLarge => "price sources disagree",
+            ConsensusError::ExponentMismatch => "prices use different exponents",
+            ConsensusError::MathOverflow => "math overflow",
+            ConsensusError::ZeroPrice => "price is zero",
+            ConsensusError::NegativePrice => "price is negative",
+        };
+        f.write_str(message)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, ConsensusError>;
+
+/// Confidence interval as a share of price, in basis points
+///
+/// # Formula
+/// ```text
+/// confidence_bps = confidence / |price| × 10000
+/// ```
+/// A zero price has no meaningful ratio and fails with `ZeroPrice`.
+pub fn confidence_bps(price: i64, confidence: u64) -> Result<u64> {
+    ratio_bps(confidence as u128, price.unsigned_abs() as u128)
+}
+
+/// Deviation of `price` from `reference`, in basis points
+///
+/// # Formula
+/// ```text
+/// deviation_bps = |price - reference| / |reference| × 10000
+/// ```
+/// A zero reference fails with `ZeroPrice`; this can only happen when the
+/// median of a signed market averages to exactly zero.
+pub fn deviation_bps(price: i64, reference: i64) -> Result<u64> {
+    let diff = (price as i128 - reference as i128).unsigned_abs();
+    ratio_bps(diff, reference.unsigned_abs() as u128)
+}
+
+fn ratio_bps(numerator: u128, denominator: u128) -> Result<u64> {
+    if denominator == 0 {
+        return Err(ConsensusError::ZeroPrice);
+    }
+
+    let bps = numerator
+        .checked_mul(BPS_DENOMINATOR)
+        .ok_or(ConsensusError::MathOverflow)?
+        .checked_div(denominator)
+        .ok_or(ConsensusError::MathOverflow)?;
+
+    u64::try_from(bps).map_err(|_| ConsensusError::MathOverflow)
+}
+
+/// Reject zero prices, and negative prices unless `allow_negative`
+pub fn check_price_sign(price: i64, allow_negative: bool) -> Result<()> {
+    if price == 0 {
+        return Err(ConsensusError::ZeroPrice);
+    }
+
+    if price < 0 && !allow_negative {
+        return Err(ConsensusError::NegativePrice);
+    }
+
+    Ok(())
+}
+
+/// Check one quote's sign, staleness and confidence
+pub fn check_quote(quote: &Quote, now: i64, params: &ValidationParams) -> Result<()> {
+    check_price_sign(quote.price, params.allow_negative)?;
+
+    let age = now.saturating_sub(quote.timestamp);
+    if age > params.max_staleness {
+        return Err(ConsensusError::StalePriceData);
+    }
+
+    if confidence_bps(quote.price, quote.confidence)? > params.max_confidence_bps {
+        return Err(ConsensusError::ConfidenceTooLarge);
+    }
+
+    Ok(())
+}
+
+/// Median of the quotes
+///
+/// Odd count: the middle quote itself. Even count: the average of the two
+/// middle prices and confidences, stamped with the newer of their timestamps.
+/// All quotes must share one exponent.
+pub fn median(quotes: &[Quote]) -> Result<Median> {
+    let first = quotes.first().ok_or(ConsensusError::NoPriceData)?;
+    if quotes.iter().any(|q| q.expo != first.expo) {
+        return Err(ConsensusError::ExponentMismatch);
+    }
+
+    // Sort indices so callers can map the result back to its source
+    let mut order: Vec<usize> = (0..quotes.len()).collect();
+    order.sort_by_key(|&i| quotes[i].price);
+
+    let len = order.len();
+    if len % 2 == 1 {
+        let index = order[len / 2];
+        return Ok(Median {
+            quote: quotes[index],
+            index: Some(index),
+        });
+    }
+
+    let mid1 = &quotes[order[len / 2 - 1]];
+    let mid2 = &quotes[order[len / 2]];
+
+    // i128 / u128 intermediates: the sum of two i64s can overflow i64
+    let price = ((mid1.price as i128 + mid2.price as i128) / 2) as i64;
+    let confidence = ((mid1.confidence as u128 + mid2.confidence as u128) / 2) as u64;
+
+    Ok(Median {
+        quote: Quote {
+            price,
+            confidence,
+            expo: mid1.expo,
+            timestamp: mid1.timestamp.max(mid2.timestamp),
+        },
+        index: None,
+    })
+}
+
+/// Check that every quote is within `max_deviation_bps` of the consensus
+pub fn check_deviation(quotes: &[Quote], consensus: &Quote, max_deviation_bps: u64) -> Result<()> {
+    for quote in quotes {
+        if deviation_bps(quote.price, consensus.price)? > max_deviation_bps {
+            return Err(ConsensusError::PriceDeviationTooLarge);
+        }
+    }
+
+    Ok(())
+}
+
+/// Full pipeline: validate every quote, take the median, check agreement
+///
+/// Any failing quote rejects the whole set; callers that prefer to drop bad
+/// quotes should filter with `check_quote` first.
+pub fn validate_consensus(quotes: &[Quote], now: i64, params: &ValidationParams) -> Result<Median> {
+    if quotes.is_empty() {
+        return Err(ConsensusError::NoPriceData);
+    }
+
+    for quote in quotes {
+        check_quote(quote, now, params)?;
+    }
+
+    let median = median(quotes)?;
+    check_deviation(quotes, &median.quote, params.max_deviation_bps)?;
+
+    Ok(median)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(price: i64) -> Quote {
+        Quote {
+            price,
+            confidence: 1,
+            expo: -8,
+            timestamp: 100,
+        }
+    }
+
+    const PARAMS: ValidationParams = ValidationParams {
+        max_staleness: 30,
+        max_confidence_bps: 100,
+        max_deviation_bps: 100,
+        allow_negative: false,
+    };
+
+    #[test]
+    fn test_median_odd_count() {
+        let median = median(&[quote(100), quote(200), quote(150)]).unwrap();
+        assert_eq!(median.quote.price, 150);
+        assert_eq!(median.index, Some(2));
+    }
+
+    #[test]
+    fn test_median_even_count() {
+        let median = median(&[quote(100), quote(200)]).unwrap();
+        assert_eq!(median.quote.price, 150);
+        assert_eq!(median.index, None);
+    }
+
+    #[test]
+    fn test_median_even_count_no_overflow() {
+        let median = median(&[quote(i64::MAX), quote(i64::MAX - 2)]).unwrap();
+        assert_eq!(median.quote.price, i64::MAX - 1);
+    }
+
+    #[test]
+    fn test_median_exponent_mismatch() {
+        let mut other = quote(100);
+        other.expo = -6;
+        assert_eq!(median(&[quote(100), other]), Err(ConsensusError::ExponentMismatch));
+    }
+
+    #[test]
+    fn test_deviation() {
+        // |50500 - 50000| / 50000 × 10000 = 100 bps
+        assert_eq!(deviation_bps(50_500, 50_000), Ok(100));
+        assert_eq!(deviation_bps(i64::MIN, i64::MAX), Ok(20_000));
+        assert_eq!(deviation_bps(-50_500, -50_000), Ok(100));
+        assert_eq!(deviation_bps(1, 0), Err(ConsensusError::ZeroPrice));
+    }
+
+    #[test]
+    fn test_confidence() {
+        assert_eq!(confidence_bps(50_000, 500), Ok(100));
+        assert_eq!(confidence_bps(i64::MIN, u64::MAX), Ok(19_999));
+        assert_eq!(confidence_bps(-50_000, 500), Ok(100));
+        assert_eq!(confidence_bps(0, 1), Err(ConsensusError::ZeroPrice));
+        assert_eq!(confidence_bps(0, 0), Err(ConsensusError::ZeroPrice));
+    }
+
+    #[test]
+    fn test_zero_and_negative_prices() {
+        assert_eq!(check_quote(&quote(0), 100, &PARAMS), Err(ConsensusError::ZeroPrice));
+        assert_eq!(check_quote(&quote(-5), 100, &PARAMS), Err(ConsensusError::NegativePrice));
+
+        let signed = ValidationParams { allow_negative: true, ..PARAMS };
+        assert_eq!(check_quote(&quote(0), 100, &signed), Err(ConsensusError::ZeroPrice));
+        assert_eq!(check_quote(&quote(-50_000), 100, &signed), Ok(()));
+
+        // Spread market: median of [-50,000, -49,990, -50,010]
+        let quotes = [quote(-50_000), quote(-49_990), quote(-50_010)];
+        assert_eq!(validate_consensus(&quotes, 100, &signed).unwrap().quote.price, -50_000);
+        assert_eq!(
+            validate_consensus(&quotes, 100, &PARAMS),
+            Err(ConsensusError::NegativePrice)
+        );
+
+        // Signed median averaging to exactly zero has no relative deviation
+        let quotes = [quote(-50_000), quote(50_000)];
+        assert_eq!(
+            validate_consensus(&quotes, 100, &ValidationParams {
+                max_confidence_bps: 10_000,
+                ..signed
+            }),
+            Err(ConsensusError::ZeroPrice)
+        );
+    }
+
+    #[test]
+    fn test_validate_consensus() {
+        let quotes = [quote(50_000), quote(50_010), quote(49_990)];
+        assert_eq!(validate_consensus(&quotes, 110, &PARAMS).unwrap().quote.price, 50_000);
+
+        // Stale by one second
+        assert_eq!(
+            validate_consensus(&quotes, 131, &PARAMS),
+            Err(ConsensusError::StalePriceData)
+        );
+
+        let quotes = [quote(50_000), quote(50_010), quote(51_000)];
+        assert_eq!(
+            validate_consensus(&quotes, 110, &PARAMS),
+            Err(ConsensusError::PriceDeviationTooLarge)
+        );
+    }
+}
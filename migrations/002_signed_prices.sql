@@ -0,0 +1,7 @@
+-- Signed Prices
+-- Spread and basis markets quote negative prices; zero is never a valid price
+ALTER TABLE price_history
+    DROP CONSTRAINT IF EXISTS price_history_price_check;
+
+ALTER TABLE price_history
+    ADD CONSTRAINT price_history_price_check CHECK (price <> 0);
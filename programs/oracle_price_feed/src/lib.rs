@@ -1,9 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use oracle_consensus::{self as consensus, ConsensusError, Quote, ValidationParams};
 
 
 declare_id!("3Lrt5g6ef2RinghQRs3LVHeut4Rap81Z28wzigmqV3kF");
 
+/// Maximum symbol length in bytes (PDA seeds are at most 32 bytes)
+pub const MAX_SYMBOL_LEN: usize = 32;
+
+/// Maximum number of whitelisted keeper keys per symbol
+pub const MAX_PUBLISHERS: usize = 8;
+
+/// Maximum number of attestation signers per symbol
+pub const MAX_ATTESTATION_SIGNERS: usize = 8;
+
+/// Maximum number of weekly open windows in a trading schedule
+pub const MAX_SESSION_WINDOWS: usize = 14;
+
+/// Maximum number of holiday overrides in a trading schedule
+pub const MAX_HOLIDAYS: usize = 16;
+
+/// Seconds an attestation timestamp may run ahead of the cluster clock
+pub const MAX_ATTESTATION_CLOCK_SKEW: i64 = 5;
+
+/// Prefix of every signed price attestation message
+pub const ATTESTATION_DOMAIN: &[u8] = b"oracle-price-feed:attestation:v1";
+
 #[program]
 pub mod oracle_price_feed {
     use super::*;
@@ -24,36 +50,48 @@ pub mod oracle_price_feed {
             .get_current_price()
             .ok_or(OracleError::NoPriceData)?;
 
-        let price_age = current_time - current_price.publish_time;
         let config = &ctx.accounts.config;
-        
-        if price_age > config.max_staleness {
-            return Err(OracleError::StalePriceData.into());
-        }
-
-        let confidence_bps = (current_price.conf as u128)
-            .checked_mul(10000)
-            .ok_or(OracleError::MathOverflow)?
-            .checked_div(current_price.price.abs() as u128)
-            .ok_or(OracleError::MathOverflow)? as u64;
-
-        if confidence_bps > config.max_confidence {
-            return Err(OracleError::ConfidenceTooLarge.into());
-        }
 
-        Ok(PriceData {
+        // Outside session the feed is expected to be old, so report that
+        // distinctly instead of as stale data
+        require!(
+            config.trading_schedule.is_open(current_time),
+            OracleError::MarketClosed
+        );
+        
+        let price_data = PriceData {
             price: current_price.price,
             confidence: current_price.conf,
             expo: current_price.expo,
             timestamp: current_price.publish_time,
             source: PriceSource::Pyth,
-        })
+        };
+
+        // Sign, staleness and confidence checks (shared with the backend)
+        consensus::check_quote(&price_data.to_quote(), current_time, &config.validation_params())
+            .map_err(OracleError::from)?;
+
+        check_price_bounds(config, &price_data)?;
+
+        Ok(price_data)
     }
 
         pub fn validate_price_consensus(
         ctx: Context<ValidatePrice>,
         prices: Vec<PriceData>,
     ) -> Result<PriceData> {
+        // Publisher prices are only trusted when read from the on-chain
+        // publisher account, never when passed in as instruction data
+        require!(
+            prices.iter().all(|p| p.source != PriceSource::Publisher),
+            OracleError::UnverifiedPublisherPrice
+        );
+
+        let mut prices = prices;
+        if let Some(publisher_price) = &ctx.accounts.publisher_price {
+            prices.push(publisher_price.to_price_data());
+        }
+
         // Need at least 1 price to validate
         require!(!prices.is_empty(), OracleError::NoPriceData);
 
@@ -61,46 +99,33 @@ pub mod oracle_price_feed {
         let clock = Clock::get()?;
         let current_time = clock.unix_timestamp;
 
-        // ============================================================
-        // STEP 1: Validate each individual price
-        // ============================================================
-        for price_data in &prices {
-            // Check staleness
-            let age = current_time - price_data.timestamp;
-            if age > config.max_staleness {
-                return Err(OracleError::StalePriceData.into());
-            }
-
-            // Check confidence (convert to basis points)
-            let confidence_bps = (price_data.confidence as u128)
-                .checked_mul(10000)
-                .ok_or(OracleError::MathOverflow)?
-                .checked_div(price_data.price.abs() as u128)
-                .ok_or(OracleError::MathOverflow)? as u64;
-
-            if confidence_bps > config.max_confidence {
-                return Err(OracleError::ConfidenceTooLarge.into());
-            }
-        }
+        require!(
+            config.trading_schedule.is_open(current_time),
+            OracleError::MarketClosed
+        );
 
         // ============================================================
-        // STEP 2: Calculate median price (most reliable)
+        // Staleness, confidence, median and deviation checks live in
+        // the shared oracle-consensus crate so the backend applies the
+        // exact same rules before serving a price
         // ============================================================
-        let median_price = calculate_median(&prices)?;
+        let quotes: Vec<Quote> = prices.iter().map(PriceData::to_quote).collect();
+        let median = consensus::validate_consensus(
+            &quotes,
+            current_time,
+            &config.validation_params(),
+        ).map_err(OracleError::from)?;
 
-        // ============================================================
-        // STEP 3: Check price deviation (are all sources agreeing?)
-        // ============================================================
-        for price_data in &prices {
-            let deviation = calculate_deviation(
-                price_data.price,
-                median_price.price
-            )?;
+        // Odd count: keep the chosen source; even count: calculated price
+        let median_price = match median.index {
+            Some(index) => prices[index].clone(),
+            None => PriceData::from_quote(&median.quote, PriceSource::Internal),
+        };
 
-            // If any price deviates too much from median, reject all
-            if deviation > config.max_deviation {
-                return Err(OracleError::PriceDeviationTooLarge.into());
-            }
+        // Absolute sanity bounds are program-only (per-market config).
+        // The even-count median is an average, so bound-check it as well
+        for price_data in prices.iter().chain(core::iter::once(&median_price)) {
+            check_price_bounds(config, price_data)?;
         }
 
         // All validations passed! Return consensus price
@@ -111,68 +136,75 @@ pub mod oracle_price_feed {
     // HELPER FUNCTIONS
     // ============================================================================
 
-    /// Calculates median price from multiple sources
-    /// 
-    /// # Why median instead of average?
-    /// Median is resistant to outliers:
-    /// - Average of [50000, 50100, 100000] = 66,700 (wrong!)
-    /// - Median of [50000, 50100, 100000] = 50,100 (correct!)
+    /// Rejects prices outside the configured absolute sanity range
     ///
-    /// The outlier (100000) doesn't skew the median.
-    fn calculate_median(prices: &Vec<PriceData>) -> Result<PriceData> {
-        require!(!prices.is_empty(), OracleError::NoPriceData);
-
-        // Create a copy and sort by price
-        let mut sorted_prices = prices.clone();
-        sorted_prices.sort_by_key(|p| p.price);
+    /// Relative checks (confidence, deviation) can't catch a bad price that
+    /// every source agrees on, e.g. BTC at $0.01 with tight confidence.
+    /// Bounds are in the config's exponent, so a price quoted at any other
+    /// exponent is rejected before they are compared.
+    fn check_price_bounds(config: &OracleConfig, price: &PriceData) -> Result<()> {
+        require!(price.expo == config.expo, OracleError::ExponentMismatch);
 
-        // Get middle element(s)
-        let len = sorted_prices.len();
-        
-        if len % 2 == 1 {
-            // Odd number of prices: return middle one
-            // Example: [100, 200, 300] → return 200
-            Ok(sorted_prices[len / 2].clone())
-        } else {
-            // Even number of prices: average the two middle ones
-            // Example: [100, 200, 300, 400] → average 200 and 300 = 250
-            let mid1 = &sorted_prices[len / 2 - 1];
-            let mid2 = &sorted_prices[len / 2];
-            
-            let avg_price = (mid1.price + mid2.price) / 2;
-            let avg_confidence = (mid1.confidence + mid2.confidence) / 2;
-            
-            Ok(PriceData {
-                price: avg_price,
-                confidence: avg_confidence,
-                expo: mid1.expo, // Assume same exponent
-                timestamp: mid1.timestamp.max(mid2.timestamp), // Use most recent
-                source: PriceSource::Internal, // This is a calculated price
-            })
+        if price.price < config.min_price || price.price > config.max_price {
+            return Err(OracleError::PriceOutOfBounds.into());
         }
+
+        Ok(())
     }
 
-    /// Calculates deviation between two prices in basis points
-    /// 
-    /// # Formula:
-    /// deviation = |price1 - price2| / price2 × 10000
+    /// Extracts (public key, message) pairs from an Ed25519 program instruction
     ///
-    /// # Example:
-    /// price1 = $50,000
-    /// price2 = $50,500
-    /// deviation = |50000 - 50500| / 50500 × 10000 = 99 bps (0.99%)
-    fn calculate_deviation(price1: i64, price2: i64) -> Result<u64> {
-        let diff = (price1 - price2).abs() as u128;
-        let base = price2.abs() as u128;
-        
-        // Calculate: (difference / base_price) × 10000
-        let deviation = diff
-            .checked_mul(10000)
-            .ok_or(OracleError::MathOverflow)?
-            .checked_div(base)
-            .ok_or(OracleError::MathOverflow)? as u64;
-        
-        Ok(deviation)
+    /// # Layout:
+    /// [num_signatures: u8][padding: u8] followed by one 14-byte offsets
+    /// record per signature (7 × u16 little-endian):
+    /// signature_offset, signature_ix_index, public_key_offset,
+    /// public_key_ix_index, message_offset, message_size, message_ix_index
+    ///
+    /// Only signatures whose key and message live inside the same
+    /// instruction (ix index = u16::MAX) are accepted, otherwise the
+    /// verified bytes could be swapped for data elsewhere in the transaction.
+    fn parse_ed25519_instruction(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+        const OFFSETS_START: usize = 2;
+        const OFFSETS_SIZE: usize = 14;
+        const CURRENT_IX: u16 = u16::MAX;
+
+        require!(data.len() >= OFFSETS_START, OracleError::InvalidEd25519Instruction);
+        let num_signatures = data[0] as usize;
+
+        let read_u16 = |at: usize| -> Result<u16> {
+            data.get(at..at + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .ok_or_else(|| OracleError::InvalidEd25519Instruction.into())
+        };
+
+        let mut entries = Vec::with_capacity(num_signatures);
+        for i in 0..num_signatures {
+            let base = OFFSETS_START + i * OFFSETS_SIZE;
+            let signature_ix = read_u16(base + 2)?;
+            let public_key_offset = read_u16(base + 4)? as usize;
+            let public_key_ix = read_u16(base + 6)?;
+            let message_offset = read_u16(base + 8)? as usize;
+            let message_size = read_u16(base + 10)? as usize;
+            let message_ix = read_u16(base + 12)?;
+
+            require!(
+                signature_ix == CURRENT_IX && public_key_ix == CURRENT_IX && message_ix == CURRENT_IX,
+                OracleError::InvalidEd25519Instruction
+            );
+
+            let public_key = data
+                .get(public_key_offset..public_key_offset + 32)
+                .ok_or(OracleError::InvalidEd25519Instruction)?;
+            let message = data
+                .get(message_offset..message_offset + message_size)
+                .ok_or(OracleError::InvalidEd25519Instruction)?;
+
+            let public_key = Pubkey::try_from(public_key)
+                .map_err(|_| OracleError::InvalidEd25519Instruction)?;
+            entries.push((public_key, message));
+        }
+
+        Ok(entries)
     }
 
     /// Initialize oracle configuration for a trading symbol
@@ -187,6 +219,10 @@ pub mod oracle_price_feed {
     /// - max_staleness: Max age in seconds (e.g., 30)
     /// - max_confidence: Max uncertainty in bps (e.g., 100 = 1%)
     /// - max_deviation: Max price difference in bps (e.g., 100 = 1%)
+    /// - min_price: Lowest sane price, at `expo`
+    /// - max_price: Highest sane price, at `expo`
+    /// - expo: Exponent every price for the symbol must be quoted at
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_config(
         ctx: Context<InitializeConfig>,
         symbol: String,
@@ -195,7 +231,12 @@ pub mod oracle_price_feed {
         max_staleness: i64,
         max_confidence: u64,
         max_deviation: u64,
+        min_price: i64,
+        max_price: i64,
+        expo: i32,
     ) -> Result<()> {
+        require!(min_price <= max_price, OracleError::InvalidPriceBounds);
+
         let config = &mut ctx.accounts.config;
         
         config.symbol = symbol;
@@ -204,12 +245,316 @@ pub mod oracle_price_feed {
         config.max_staleness = max_staleness;
         config.max_confidence = max_confidence;
         config.max_deviation = max_deviation;
+        config.min_price = min_price;
+        config.max_price = max_price;
         config.authority = ctx.accounts.authority.key();
         config.bump = ctx.bumps.config;
+        config.publishers = Vec::new();
+        config.attestation_signers = Vec::new();
+        config.attestation_threshold = 0;
+        config.trading_schedule = TradingSchedule::default();
+        config.allow_negative_prices = false;
+        config.expo = expo;
         
         Ok(())
     }
 
+    /// Rewrite a config created before fields were appended to `OracleConfig`
+    ///
+    /// # Purpose:
+    /// Accounts from the first deployment hold only the legacy fields and
+    /// were sized for them, so they no longer deserialize. The account is
+    /// grown to `OracleConfig::SPACE` (the authority pays the extra rent)
+    /// and the appended fields get their defaults: no publishers or
+    /// attestation signers, unbounded prices, a 24/7 schedule and no
+    /// negative prices.
+    ///
+    /// # Parameters:
+    /// - expo: Exponent the symbol's feeds quote at
+    ///
+    /// # Errors:
+    /// - ConfigAlreadyMigrated: the account already has the current size
+    pub fn migrate_config(ctx: Context<MigrateConfig>, expo: i32) -> Result<()> {
+        let config_info = ctx.accounts.config.to_account_info();
+        require!(
+            config_info.data_len() < OracleConfig::SPACE,
+            OracleError::ConfigAlreadyMigrated
+        );
+
+        let legacy = {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data.starts_with(OracleConfig::DISCRIMINATOR),
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            LegacyOracleConfig::deserialize(&mut &data[OracleConfig::DISCRIMINATOR.len()..])
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+        };
+
+        require_keys_eq!(legacy.authority, ctx.accounts.authority.key(), ErrorCode::ConstraintHasOne);
+        let address = Pubkey::create_program_address(
+            &[b"oracle-config", legacy.symbol.as_bytes(), &[legacy.bump]],
+            ctx.program_id,
+        ).map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(address, config_info.key(), ErrorCode::ConstraintSeeds);
+
+        let rent = Rent::get()?.minimum_balance(OracleConfig::SPACE);
+        let top_up = rent.saturating_sub(config_info.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: config_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        config_info.resize(OracleConfig::SPACE)?;
+
+        let config = OracleConfig {
+            symbol: legacy.symbol,
+            pyth_feed: legacy.pyth_feed,
+            switchboard_aggregator: legacy.switchboard_aggregator,
+            max_staleness: legacy.max_staleness,
+            max_confidence: legacy.max_confidence,
+            max_deviation: legacy.max_deviation,
+            authority: legacy.authority,
+            bump: legacy.bump,
+            publishers: Vec::new(),
+            attestation_signers: Vec::new(),
+            attestation_threshold: 0,
+            min_price: i64::MIN,
+            max_price: i64::MAX,
+            trading_schedule: TradingSchedule::default(),
+            allow_negative_prices: false,
+            expo,
+        };
+        let mut data = config_info.try_borrow_mut_data()?;
+        config.try_serialize(&mut &mut data[..])?;
+
+        Ok(())
+    }
+
+    /// Update the absolute sanity bounds for a symbol
+    ///
+    /// # Parameters:
+    /// - min_price: Lowest sane price, at the config's `expo`
+    /// - max_price: Highest sane price, at the config's `expo`
+    pub fn set_price_bounds(
+        ctx: Context<SetPriceBounds>,
+        min_price: i64,
+        max_price: i64,
+    ) -> Result<()> {
+        require!(min_price <= max_price, OracleError::InvalidPriceBounds);
+
+        let config = &mut ctx.accounts.config;
+        config.min_price = min_price;
+        config.max_price = max_price;
+        Ok(())
+    }
+
+    /// Replace the trading schedule for a symbol
+    ///
+    /// An empty schedule (no windows) means the market trades 24/7.
+    ///
+    /// # Parameters:
+    /// - schedule: Weekly UTC open windows plus holiday overrides
+    pub fn set_trading_schedule(
+        ctx: Context<SetTradingSchedule>,
+        schedule: TradingSchedule,
+    ) -> Result<()> {
+        schedule.validate()?;
+        ctx.accounts.config.trading_schedule = schedule;
+        Ok(())
+    }
+
+    /// Report whether the symbol's market is currently in session
+    pub fn get_market_status(ctx: Context<GetMarketStatus>) -> Result<MarketStatus> {
+        let clock = Clock::get()?;
+
+        if ctx.accounts.config.trading_schedule.is_open(clock.unix_timestamp) {
+            Ok(MarketStatus::Open)
+        } else {
+            Ok(MarketStatus::Closed)
+        }
+    }
+
+    /// Allow or reject negative prices for a symbol
+    ///
+    /// Spread and basis markets can legitimately trade below zero; every
+    /// other market should keep this off. Zero prices are always rejected.
+    pub fn set_allow_negative_prices(
+        ctx: Context<SetAllowNegativePrices>,
+        allow_negative_prices: bool,
+    ) -> Result<()> {
+        ctx.accounts.config.allow_negative_prices = allow_negative_prices;
+        Ok(())
+    }
+
+    /// Replace the set of keeper keys allowed to push prices for a symbol
+    ///
+    /// # Parameters:
+    /// - publishers: Whitelisted keeper keys (at most `MAX_PUBLISHERS`)
+    pub fn set_publishers(ctx: Context<SetPublishers>, publishers: Vec<Pubkey>) -> Result<()> {
+        require!(
+            publishers.len() <= MAX_PUBLISHERS,
+            OracleError::TooManyPublishers
+        );
+
+        ctx.accounts.config.publishers = publishers;
+        Ok(())
+    }
+
+    /// Configure the m-of-n signer set for signed price attestations
+    ///
+    /// # Parameters:
+    /// - signers: Ed25519 keys allowed to sign attestations
+    /// - threshold: Distinct signatures required (m)
+    pub fn set_attestation_signers(
+        ctx: Context<SetAttestationSigners>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            signers.len() <= MAX_ATTESTATION_SIGNERS
+                && threshold > 0
+                && threshold as usize <= signers.len(),
+            OracleError::InvalidAttestationSigners
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.attestation_signers = signers;
+        config.attestation_threshold = threshold;
+        Ok(())
+    }
+
+    /// Verify a signed price attestation and return it as price data
+    ///
+    /// # How it works:
+    /// The transaction must contain Ed25519 sig-verify instructions before
+    /// this one. The runtime has already checked those signatures; here we
+    /// read them back through the instructions sysvar and count how many
+    /// configured signers signed exactly `attestation.message()`.
+    ///
+    /// # Errors:
+    /// - InsufficientAttestationSignatures: fewer than m signers matched
+    /// - StalePriceData: attestation older than `max_staleness`
+    /// - FutureTimestamp: attestation more than `MAX_ATTESTATION_CLOCK_SKEW`
+    ///   seconds ahead of the cluster clock
+    /// - ConfidenceTooLarge: confidence above `max_confidence` bps
+    pub fn verify_price_attestation(
+        ctx: Context<VerifyPriceAttestation>,
+        attestation: PriceAttestation,
+    ) -> Result<PriceData> {
+        let config = &ctx.accounts.config;
+        require!(
+            attestation.symbol == config.symbol,
+            OracleError::InvalidPriceFeed
+        );
+        require!(
+            config.attestation_threshold > 0,
+            OracleError::InvalidAttestationSigners
+        );
+
+        let message = attestation.message()?;
+        let instructions = &ctx.accounts.instructions;
+        let current_index = load_current_index_checked(instructions)?;
+
+        // Collect distinct configured signers that signed this message
+        let mut verified_signers: Vec<Pubkey> = Vec::new();
+        for index in 0..current_index {
+            let ix = load_instruction_at_checked(index as usize, instructions)?;
+            if ix.program_id != ed25519_program::ID {
+                continue;
+            }
+
+            for (signer, signed_message) in parse_ed25519_instruction(&ix.data)? {
+                if signed_message == message.as_slice()
+                    && config.attestation_signers.contains(&signer)
+                    && !verified_signers.contains(&signer)
+                {
+                    verified_signers.push(signer);
+                }
+            }
+        }
+
+        require!(
+            verified_signers.len() >= config.attestation_threshold as usize,
+            OracleError::InsufficientAttestationSignatures
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            config.trading_schedule.is_open(clock.unix_timestamp),
+            OracleError::MarketClosed
+        );
+
+        // A signer's clock may run slightly ahead of the cluster's, but a
+        // price from further out would pass every staleness check
+        require!(
+            attestation.timestamp <= clock.unix_timestamp.saturating_add(MAX_ATTESTATION_CLOCK_SKEW),
+            OracleError::FutureTimestamp
+        );
+
+        let price_data = PriceData {
+            price: attestation.price,
+            confidence: attestation.confidence,
+            expo: attestation.expo,
+            timestamp: attestation.timestamp,
+            source: PriceSource::Attestation,
+        };
+
+        // Sign, staleness and confidence checks (shared with the backend)
+        consensus::check_quote(&price_data.to_quote(), clock.unix_timestamp, &config.validation_params())
+            .map_err(OracleError::from)?;
+
+        check_price_bounds(config, &price_data)?;
+
+        Ok(price_data)
+    }
+
+    /// Push a keeper price into the symbol's publisher account
+    ///
+    /// # Purpose:
+    /// Long-tail assets have no Pyth/Switchboard feed, so a whitelisted
+    /// keeper signs and submits the price itself. The latest submission
+    /// is stored per symbol and read by `validate_price_consensus`.
+    ///
+    /// # Parameters:
+    /// - symbol: Trading pair name (e.g., "BONK/USD")
+    /// - price: Price in units of 10^expo
+    /// - confidence: Confidence interval in units of 10^expo
+    /// - expo: Price exponent
+    pub fn submit_price(
+        ctx: Context<SubmitPrice>,
+        symbol: String,
+        price: i64,
+        confidence: u64,
+        expo: i32,
+    ) -> Result<()> {
+        let publisher = ctx.accounts.publisher.key();
+        require!(
+            ctx.accounts.config.publishers.contains(&publisher),
+            OracleError::UnauthorizedPublisher
+        );
+
+        let clock = Clock::get()?;
+        let publisher_price = &mut ctx.accounts.publisher_price;
+
+        publisher_price.symbol = symbol;
+        publisher_price.price = price;
+        publisher_price.confidence = confidence;
+        publisher_price.expo = expo;
+        publisher_price.timestamp = clock.unix_timestamp;
+        publisher_price.publisher = publisher;
+        publisher_price.bump = ctx.bumps.publisher_price;
+
+        Ok(())
+    }
+
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         msg!("Greetings from: {:?}", ctx.program_id);
         Ok(())
@@ -228,12 +573,137 @@ pub struct PriceData {
     pub source: PriceSource,
 }
 
+impl PriceData {
+    pub fn to_quote(&self) -> Quote {
+        Quote {
+            price: self.price,
+            confidence: self.confidence,
+            expo: self.expo,
+            timestamp: self.timestamp,
+        }
+    }
+
+    pub fn from_quote(quote: &Quote, source: PriceSource) -> Self {
+        PriceData {
+            price: quote.price,
+            confidence: quote.confidence,
+            expo: quote.expo,
+            timestamp: quote.timestamp,
+            source,
+        }
+    }
+}
+
 /// Enum to identify which oracle provided the price
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
 pub enum PriceSource {
     Pyth,        // From Pyth Network
     Switchboard, // From Switchboard
     Internal,    // Calculated internally (consensus)
+    Publisher,   // Pushed by a whitelisted keeper
+    Attestation, // Signed off-chain consensus, verified via Ed25519
+}
+
+/// Whether a market is inside a trading session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub enum MarketStatus {
+    Open,
+    Closed,
+}
+
+/// Weekly open window in UTC, e.g. Monday 13:30–20:00
+///
+/// Sessions crossing midnight are split into one window per day.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SessionWindow {
+    /// Day of week, 0 = Monday … 6 = Sunday
+    pub day: u8,
+    /// Minutes after UTC midnight the session opens (inclusive)
+    pub open_minute: u16,
+    /// Minutes after UTC midnight the session closes (exclusive)
+    pub close_minute: u16,
+}
+
+/// Replaces the weekly windows for one UTC calendar day
+///
+/// `open_minute == close_minute` closes the market for the whole day;
+/// otherwise the override describes a shortened session.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HolidayOverride {
+    /// Days since the Unix epoch (UTC)
+    pub date: i64,
+    pub open_minute: u16,
+    pub close_minute: u16,
+}
+
+/// Trading schedule for non-24/7 markets (FX, equities)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct TradingSchedule {
+    pub windows: Vec<SessionWindow>,
+    pub holidays: Vec<HolidayOverride>,
+}
+
+impl TradingSchedule {
+    const SECONDS_PER_DAY: i64 = 86_400;
+    const MINUTES_PER_DAY: u16 = 1_440;
+
+    /// Returns true if the market is in session at `unix_timestamp`
+    pub fn is_open(&self, unix_timestamp: i64) -> bool {
+        // No windows configured: 24/7 market
+        if self.windows.is_empty() {
+            return true;
+        }
+
+        let date = unix_timestamp.div_euclid(Self::SECONDS_PER_DAY);
+        let minute = (unix_timestamp.rem_euclid(Self::SECONDS_PER_DAY) / 60) as u16;
+
+        if let Some(holiday) = self.holidays.iter().find(|h| h.date == date) {
+            return minute >= holiday.open_minute && minute < holiday.close_minute;
+        }
+
+        // 1970-01-01 was a Thursday (day 3 with Monday = 0)
+        let day = (date + 3).rem_euclid(7) as u8;
+        self.windows.iter().any(|w| {
+            w.day == day && minute >= w.open_minute && minute < w.close_minute
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.windows.len() <= MAX_SESSION_WINDOWS && self.holidays.len() <= MAX_HOLIDAYS,
+            OracleError::InvalidTradingSchedule
+        );
+
+        let windows_valid = self.windows.iter().all(|w| {
+            w.day < 7 && w.open_minute < w.close_minute && w.close_minute <= Self::MINUTES_PER_DAY
+        });
+        let holidays_valid = self.holidays.iter().all(|h| {
+            h.open_minute <= h.close_minute && h.close_minute <= Self::MINUTES_PER_DAY
+        });
+        require!(windows_valid && holidays_valid, OracleError::InvalidTradingSchedule);
+
+        Ok(())
+    }
+}
+
+/// Off-chain price signed by the attestation signer set
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PriceAttestation {
+    pub symbol: String,
+    pub price: i64,
+    pub confidence: u64,
+    pub expo: i32,
+    pub timestamp: i64,
+}
+
+impl PriceAttestation {
+    /// Bytes the signers sign: domain prefix followed by the Borsh encoding
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = ATTESTATION_DOMAIN.to_vec();
+        self.serialize(&mut message)
+            .map_err(|_| OracleError::InvalidEd25519Instruction)?;
+        Ok(message)
+    }
 }
 
 /// Configuration for a trading symbol (e.g., BTC/USD)
@@ -254,6 +724,100 @@ pub struct OracleConfig {
     pub authority: Pubkey,
 
     pub bump: u8,
+
+    // Fields below were added after the first deployment; new fields go at
+    // the end and `migrate_config` fills them in for older accounts
+
+    pub publishers: Vec<Pubkey>,
+
+    pub attestation_signers: Vec<Pubkey>,
+
+    pub attestation_threshold: u8,
+
+    pub min_price: i64,
+
+    pub max_price: i64,
+
+    pub trading_schedule: TradingSchedule,
+
+    pub allow_negative_prices: bool,
+
+    /// Exponent of every accepted price and of `min_price` / `max_price`
+    pub expo: i32,
+}
+
+impl OracleConfig {
+    /// Account size with every Vec at its maximum length
+    pub const SPACE: usize = 8 + // Discriminator
+        4 + MAX_SYMBOL_LEN + // symbol (String)
+        32 + // pyth_feed (Pubkey)
+        32 + // switchboard_aggregator (Pubkey)
+        8 +  // max_staleness (i64)
+        8 +  // max_confidence (u64)
+        8 +  // max_deviation (u64)
+        32 + // authority (Pubkey)
+        1 +  // bump (u8)
+        4 + 32 * MAX_PUBLISHERS + // publishers (Vec<Pubkey>)
+        4 + 32 * MAX_ATTESTATION_SIGNERS + // attestation_signers (Vec<Pubkey>)
+        1 +  // attestation_threshold (u8)
+        8 +  // min_price (i64)
+        8 +  // max_price (i64)
+        4 + 5 * MAX_SESSION_WINDOWS + // trading_schedule.windows
+        4 + 12 * MAX_HOLIDAYS + // trading_schedule.holidays
+        1 +  // allow_negative_prices (bool)
+        4;   // expo (i32)
+
+    pub fn validation_params(&self) -> ValidationParams {
+        ValidationParams {
+            max_staleness: self.max_staleness,
+            max_confidence_bps: self.max_confidence,
+            max_deviation_bps: self.max_deviation,
+            allow_negative: self.allow_negative_prices,
+        }
+    }
+}
+
+/// `OracleConfig` as first deployed, before any fields were appended
+#[derive(AnchorDeserialize)]
+pub struct LegacyOracleConfig {
+    pub symbol: String,
+    pub pyth_feed: Pubkey,
+    pub switchboard_aggregator: Pubkey,
+    pub max_staleness: i64,
+    pub max_confidence: u64,
+    pub max_deviation: u64,
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+/// Latest keeper-submitted price for a symbol
+#[account]
+pub struct PublisherPrice {
+    pub symbol: String,
+
+    pub price: i64,
+
+    pub confidence: u64,
+
+    pub expo: i32,
+
+    pub timestamp: i64,
+
+    pub publisher: Pubkey,
+
+    pub bump: u8,
+}
+
+impl PublisherPrice {
+    pub fn to_price_data(&self) -> PriceData {
+        PriceData {
+            price: self.price,
+            confidence: self.confidence,
+            expo: self.expo,
+            timestamp: self.timestamp,
+            source: PriceSource::Publisher,
+        }
+    }
 }
 
 /// Custom error codes for our program
@@ -276,6 +840,66 @@ pub enum OracleError {
     
     #[msg("Math overflow in calculation")]
     MathOverflow,
+
+    #[msg("Signer is not a whitelisted publisher")]
+    UnauthorizedPublisher,
+
+    #[msg("Too many publishers for one symbol")]
+    TooManyPublishers,
+
+    #[msg("Publisher prices must come from the publisher account")]
+    UnverifiedPublisherPrice,
+
+    #[msg("Invalid attestation signer set or threshold")]
+    InvalidAttestationSigners,
+
+    #[msg("Not enough valid attestation signatures")]
+    InsufficientAttestationSignatures,
+
+    #[msg("Malformed Ed25519 signature instruction")]
+    InvalidEd25519Instruction,
+
+    #[msg("Price outside configured sanity bounds")]
+    PriceOutOfBounds,
+
+    #[msg("Minimum price bound exceeds maximum")]
+    InvalidPriceBounds,
+
+    #[msg("Market is closed (outside trading session)")]
+    MarketClosed,
+
+    #[msg("Invalid trading schedule")]
+    InvalidTradingSchedule,
+
+    #[msg("Prices use different exponents")]
+    ExponentMismatch,
+
+    #[msg("Price is zero")]
+    ZeroPrice,
+
+    #[msg("Negative price on a market that does not allow it")]
+    NegativePrice,
+
+    #[msg("Price timestamp is ahead of the cluster clock")]
+    FutureTimestamp,
+
+    #[msg("Oracle config already has the current layout")]
+    ConfigAlreadyMigrated,
+}
+
+impl From<ConsensusError> for OracleError {
+    fn from(error: ConsensusError) -> Self {
+        match error {
+            ConsensusError::NoPriceData => OracleError::NoPriceData,
+            ConsensusError::StalePriceData => OracleError::StalePriceData,
+            ConsensusError::ConfidenceTooLarge => OracleError::ConfidenceTooLarge,
+            ConsensusError::PriceDeviationTooLarge => OracleError::PriceDeviationTooLarge,
+            ConsensusError::ExponentMismatch => OracleError::ExponentMismatch,
+            ConsensusError::MathOverflow => OracleError::MathOverflow,
+            ConsensusError::ZeroPrice => OracleError::ZeroPrice,
+            ConsensusError::NegativePrice => OracleError::NegativePrice,
+        }
+    }
 }
 
 
@@ -301,6 +925,12 @@ pub struct ValidatePrice<'info> {
         bump = config.bump,
     )]
     pub config: Account<'info, OracleConfig>,
+
+    #[account(
+        seeds = [b"publisher-price", config.symbol.as_bytes()],
+        bump = publisher_price.bump,
+    )]
+    pub publisher_price: Option<Account<'info, PublisherPrice>>,
 }
 
 #[derive(Accounts)]
@@ -309,15 +939,7 @@ pub struct InitializeConfig<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + // Discriminator
-                32 + // symbol (String max 32 bytes)
-                32 + // pyth_feed (Pubkey)
-                32 + // switchboard_aggregator (Pubkey)
-                8 +  // max_staleness (i64)
-                8 +  // max_confidence (u64)
-                8 +  // max_deviation (u64)
-                32 + // authority (Pubkey)
-                1,   // bump (u8)
+        space = OracleConfig::SPACE,
         seeds = [b"oracle-config", symbol.as_bytes()],
         bump
     )]
@@ -329,4 +951,134 @@ pub struct InitializeConfig<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetPriceBounds<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTradingSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetMarketStatus<'info> {
+    #[account(
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, OracleConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowNegativePrices<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPublishers<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(symbol: String)]
+pub struct SubmitPrice<'info> {
+    #[account(
+        seeds = [b"oracle-config", symbol.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = publisher,
+        space = 8 + // Discriminator
+                4 + MAX_SYMBOL_LEN + // symbol (String)
+                8 +  // price (i64)
+                8 +  // confidence (u64)
+                4 +  // expo (i32)
+                8 +  // timestamp (i64)
+                32 + // publisher (Pubkey)
+                1,   // bump (u8)
+        seeds = [b"publisher-price", symbol.as_bytes()],
+        bump
+    )]
+    pub publisher_price: Account<'info, PublisherPrice>,
+
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: still in the legacy layout, so it can't be loaded as
+    /// `OracleConfig`; discriminator, seeds and authority are checked by
+    /// `migrate_config`
+    #[account(mut, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttestationSigners<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, OracleConfig>,
 
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyPriceAttestation<'info> {
+    #[account(
+        seeds = [b"oracle-config", config.symbol.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, OracleConfig>,
+
+    /// CHECK: address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
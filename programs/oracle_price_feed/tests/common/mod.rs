@@ -0,0 +1,227 @@
+//! Shared harness for the program integration tests
+//!
+//! Runs the compiled program in-process with LiteSVM, so the suite needs no
+//! validator or network. Oracle accounts are fabricated byte-for-byte from
+//! the Pyth and Switchboard SDK structs.
+//!
+//! Build the program first: `anchor build` (produces
+//! `target/deploy/oracle_price_feed.so`), then `cargo test -p oracle_price_feed`.
+
+#![allow(dead_code)]
+
+use anchor_lang::solana_program::hash::hash;
+use litesvm::LiteSVM;
+use oracle_price_feed::{OracleError, PriceData, PriceSource};
+use oracle_price_feed_client::instructions::{self, ConfigParams};
+use pyth_sdk_solana::state::{AccountType, PriceStatus, SolanaPriceAccount, MAGIC, VERSION_2};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use switchboard_v2::{AggregatorAccountData, SwitchboardDecimal};
+
+/// Start of the test clock (2023-12-10 18:56:07 UTC, a Sunday)
+pub const START_TIME: i64 = 1_702_234_567;
+
+/// Exponent used for every fabricated price
+pub const EXPO: i32 = -8;
+
+/// $1 at `EXPO`
+pub const ONE_DOLLAR: i64 = 100_000_000;
+
+pub const MAX_STALENESS: i64 = 30;
+pub const MAX_CONFIDENCE_BPS: u64 = 100;
+pub const MAX_DEVIATION_BPS: u64 = 100;
+
+pub struct TestContext {
+    pub svm: LiteSVM,
+    pub authority: Keypair,
+}
+
+impl TestContext {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        let program_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../target/deploy/oracle_price_feed.so"
+        );
+        svm.add_program_from_file(oracle_price_feed::ID, program_path)
+            .expect("missing target/deploy/oracle_price_feed.so, run `anchor build` first");
+
+        let authority = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 10_000_000_000).unwrap();
+
+        let mut ctx = Self { svm, authority };
+        ctx.set_time(START_TIME);
+        ctx
+    }
+
+    pub fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.svm.get_sysvar();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    pub fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm.airdrop(&keypair.pubkey(), 1_000_000_000).unwrap();
+        keypair
+    }
+
+    /// Default risk parameters; bounds allow $1 – $1,000,000 at `EXPO`
+    pub fn default_params(pyth_feed: Pubkey) -> ConfigParams {
+        ConfigParams {
+            pyth_feed,
+            switchboard_aggregator: Pubkey::new_unique(),
+            max_staleness: MAX_STALENESS,
+            max_confidence: MAX_CONFIDENCE_BPS,
+            max_deviation: MAX_DEVIATION_BPS,
+            min_price: ONE_DOLLAR,
+            max_price: 1_000_000 * ONE_DOLLAR,
+            expo: EXPO,
+        }
+    }
+
+    pub fn init_config(&mut self, symbol: &str, params: ConfigParams) {
+        let ix = instructions::initialize_config(self.authority.pubkey(), symbol, params);
+        self.send_as_authority(&[ix]).expect("initialize_config failed");
+    }
+
+    /// Send a transaction signed by `signers` (first signer pays)
+    ///
+    /// Returns the last return data written by the oracle program.
+    pub fn send(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<u8>, TransactionError> {
+        // Identical transactions would otherwise be rejected as duplicates
+        self.svm.expire_blockhash();
+
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+
+        self.svm
+            .send_transaction(tx)
+            .map(|meta| meta.return_data.data)
+            .map_err(|failed| failed.err)
+    }
+
+    pub fn send_as_authority(&mut self, ixs: &[Instruction]) -> Result<Vec<u8>, TransactionError> {
+        let authority = self.authority.insecure_clone();
+        self.send(ixs, &[&authority])
+    }
+
+    /// Write a Pyth price account with a Trading aggregate
+    pub fn set_pyth_account(&mut self, price: i64, conf: u64, publish_time: i64) -> Pubkey {
+        self.set_pyth_account_with_expo(price, conf, EXPO, publish_time)
+    }
+
+    /// Write a Pyth price account with a Trading aggregate at `expo`
+    pub fn set_pyth_account_with_expo(&mut self, price: i64, conf: u64, expo: i32, publish_time: i64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.svm
+            .set_account(address, Account {
+                lamports: 1_000_000_000,
+                data: pyth_account_bytes_with_expo(price, conf, expo, publish_time),
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            })
+            .unwrap();
+        address
+    }
+}
+
+/// Serialize a Pyth v2 price account
+pub fn pyth_account_bytes(price: i64, conf: u64, publish_time: i64) -> Vec<u8> {
+    pyth_account_bytes_with_expo(price, conf, EXPO, publish_time)
+}
+
+/// Serialize a Pyth v2 price account at `expo`
+pub fn pyth_account_bytes_with_expo(price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+    let mut account: SolanaPriceAccount = bytemuck::Zeroable::zeroed();
+    account.magic = MAGIC;
+    account.ver = VERSION_2;
+    account.atype = AccountType::Price as u32;
+    account.size = std::mem::size_of::<SolanaPriceAccount>() as u32;
+    account.expo = expo;
+    account.timestamp = publish_time;
+    account.agg.price = price;
+    account.agg.conf = conf;
+    account.agg.status = PriceStatus::Trading;
+
+    bytemuck::bytes_of(&account).to_vec()
+}
+
+/// Serialize a Switchboard v2 aggregator account with one confirmed round
+pub fn switchboard_account_bytes(result: f64, std_deviation: f64, round_open_timestamp: i64) -> Vec<u8> {
+    let mut aggregator: AggregatorAccountData = bytemuck::Zeroable::zeroed();
+    aggregator.latest_confirmed_round.result = SwitchboardDecimal::from_f64(result);
+    aggregator.latest_confirmed_round.std_deviation = SwitchboardDecimal::from_f64(std_deviation);
+    aggregator.latest_confirmed_round.round_open_timestamp = round_open_timestamp;
+
+    let discriminator = &hash(b"account:AggregatorAccountData").to_bytes()[..8];
+    [discriminator, bytemuck::bytes_of(&aggregator)].concat()
+}
+
+/// Decode a fabricated Switchboard account the same way a keeper would
+/// before handing it to `validate_price_consensus`
+pub fn switchboard_price(account_bytes: &[u8]) -> PriceData {
+    let aggregator = AggregatorAccountData::new_from_bytes(account_bytes).unwrap();
+    let round = &aggregator.latest_confirmed_round;
+
+    let to_expo = |value: &SwitchboardDecimal| -> i128 {
+        let shift = -EXPO - value.scale as i32;
+        if shift >= 0 {
+            value.mantissa * 10_i128.pow(shift as u32)
+        } else {
+            value.mantissa / 10_i128.pow((-shift) as u32)
+        }
+    };
+
+    PriceData {
+        price: to_expo(&round.result) as i64,
+        confidence: to_expo(&round.std_deviation) as u64,
+        expo: EXPO,
+        timestamp: round.round_open_timestamp,
+        source: PriceSource::Switchboard,
+    }
+}
+
+pub fn pyth_price(price: i64, confidence: u64, timestamp: i64) -> PriceData {
+    PriceData {
+        price,
+        confidence,
+        expo: EXPO,
+        timestamp,
+        source: PriceSource::Pyth,
+    }
+}
+
+/// Assert that instruction `index` failed with `expected`
+pub fn assert_oracle_error<T: std::fmt::Debug>(
+    result: Result<T, TransactionError>,
+    index: u8,
+    expected: OracleError,
+) {
+    let code: u32 = expected.into();
+    match result {
+        Err(TransactionError::InstructionError(i, InstructionError::Custom(c))) => {
+            assert_eq!((i, c), (index, code), "unexpected custom error");
+        }
+        other => panic!("expected custom error {} at ix {}, got {:?}", code, index, other),
+    }
+}
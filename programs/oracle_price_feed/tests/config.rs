@@ -0,0 +1,281 @@
+mod common;
+
+use common::*;
+use anchor_lang::Discriminator;
+use oracle_price_feed::{
+    HolidayOverride, MarketStatus, OracleConfig, OracleError, SessionWindow, TradingSchedule,
+    MAX_ATTESTATION_SIGNERS, MAX_HOLIDAYS, MAX_PUBLISHERS, MAX_SESSION_WINDOWS, MAX_SYMBOL_LEN,
+};
+use oracle_price_feed_client::{decode, instructions, pda};
+use solana_sdk::{account::Account, pubkey::Pubkey, signature::Signer};
+
+const SYMBOL: &str = "EUR/USD";
+
+fn setup() -> (TestContext, Pubkey) {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(2 * ONE_DOLLAR, 0, START_TIME);
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+    (ctx, feed)
+}
+
+/// Monday–Friday, all day
+fn weekdays() -> TradingSchedule {
+    TradingSchedule {
+        windows: (0..5)
+            .map(|day| SessionWindow { day, open_minute: 0, close_minute: 1_440 })
+            .collect(),
+        holidays: vec![],
+    }
+}
+
+#[test]
+fn test_initialize_config() {
+    let (ctx, feed) = setup();
+
+    let account = ctx.svm.get_account(&pda::find_config_address(SYMBOL).0).unwrap();
+    let config = decode::decode_oracle_config(&account.data).unwrap();
+
+    assert_eq!(config.symbol, SYMBOL);
+    assert_eq!(config.pyth_feed, feed);
+    assert_eq!(config.max_staleness, MAX_STALENESS);
+    assert_eq!(config.authority, ctx.authority.pubkey());
+    assert!(config.publishers.is_empty());
+    assert!(config.trading_schedule.windows.is_empty());
+}
+
+#[test]
+fn test_initialize_config_rejects_inverted_bounds() {
+    let mut ctx = TestContext::new();
+    let mut params = TestContext::default_params(Pubkey::new_unique());
+    params.min_price = params.max_price + 1;
+
+    let result = ctx.send_as_authority(&[instructions::initialize_config(ctx.authority.pubkey(), SYMBOL, params)]);
+    assert_oracle_error(result, 0, OracleError::InvalidPriceBounds);
+}
+
+#[test]
+fn test_set_price_bounds() {
+    let (mut ctx, feed) = setup();
+
+    // Narrow bounds to exclude the $2 feed price
+    ctx.send_as_authority(&[instructions::set_price_bounds(
+        ctx.authority.pubkey(),
+        SYMBOL,
+        3 * ONE_DOLLAR,
+        4 * ONE_DOLLAR,
+    )])
+    .unwrap();
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::PriceOutOfBounds);
+}
+
+#[test]
+fn test_admin_instructions_require_authority() {
+    let (mut ctx, _) = setup();
+    let intruder = ctx.funded_keypair();
+
+    let result = ctx.send(
+        &[instructions::set_price_bounds(intruder.pubkey(), SYMBOL, 0, i64::MAX)],
+        &[&intruder],
+    );
+    assert!(result.is_err());
+
+    let result = ctx.send(
+        &[instructions::set_publishers(intruder.pubkey(), SYMBOL, vec![intruder.pubkey()])],
+        &[&intruder],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_set_publishers_limit() {
+    let (mut ctx, _) = setup();
+    let too_many = (0..=oracle_price_feed::MAX_PUBLISHERS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+
+    let result = ctx.send_as_authority(&[instructions::set_publishers(ctx.authority.pubkey(), SYMBOL, too_many)]);
+    assert_oracle_error(result, 0, OracleError::TooManyPublishers);
+}
+
+#[test]
+fn test_submit_price() {
+    let (mut ctx, _) = setup();
+    let keeper = ctx.funded_keypair();
+    let outsider = ctx.funded_keypair();
+    ctx.send_as_authority(&[instructions::set_publishers(ctx.authority.pubkey(), SYMBOL, vec![keeper.pubkey()])])
+        .unwrap();
+
+    let result = ctx.send(
+        &[instructions::submit_price(outsider.pubkey(), SYMBOL, 2 * ONE_DOLLAR, 0, EXPO)],
+        &[&outsider],
+    );
+    assert_oracle_error(result, 0, OracleError::UnauthorizedPublisher);
+
+    ctx.send(
+        &[instructions::submit_price(keeper.pubkey(), SYMBOL, 2 * ONE_DOLLAR, 1_000, EXPO)],
+        &[&keeper],
+    )
+    .unwrap();
+
+    let account = ctx.svm.get_account(&pda::find_publisher_price_address(SYMBOL).0).unwrap();
+    let stored = decode::decode_publisher_price(&account.data).unwrap();
+    assert_eq!(stored.price, 2 * ONE_DOLLAR);
+    assert_eq!(stored.confidence, 1_000);
+    assert_eq!(stored.timestamp, START_TIME);
+    assert_eq!(stored.publisher, keeper.pubkey());
+}
+
+#[test]
+fn test_market_closed_on_weekend() {
+    let (mut ctx, feed) = setup();
+    ctx.send_as_authority(&[instructions::set_trading_schedule(ctx.authority.pubkey(), SYMBOL, weekdays())])
+        .unwrap();
+
+    // START_TIME is a Sunday
+    let data = ctx.send_as_authority(&[instructions::get_market_status(SYMBOL)]).unwrap();
+    assert_eq!(decode::decode_market_status(&data).unwrap(), MarketStatus::Closed);
+
+    // A weekend-old price is reported as closed, not stale
+    ctx.set_time(START_TIME + 3_600);
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::MarketClosed);
+
+    let prices = vec![pyth_price(2 * ONE_DOLLAR, 0, START_TIME)];
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::MarketClosed);
+}
+
+#[test]
+fn test_market_open_on_weekday_except_holiday() {
+    let (mut ctx, _) = setup();
+    // Monday after START_TIME
+    let monday_noon = (START_TIME / 86_400 + 1) * 86_400 + 12 * 3_600;
+    let mut schedule = weekdays();
+    schedule.holidays.push(HolidayOverride {
+        date: monday_noon / 86_400 + 1, // Tuesday closed
+        open_minute: 0,
+        close_minute: 0,
+    });
+    ctx.send_as_authority(&[instructions::set_trading_schedule(ctx.authority.pubkey(), SYMBOL, schedule)])
+        .unwrap();
+
+    ctx.set_time(monday_noon);
+    let data = ctx.send_as_authority(&[instructions::get_market_status(SYMBOL)]).unwrap();
+    assert_eq!(decode::decode_market_status(&data).unwrap(), MarketStatus::Open);
+
+    ctx.set_time(monday_noon + 86_400);
+    let data = ctx.send_as_authority(&[instructions::get_market_status(SYMBOL)]).unwrap();
+    assert_eq!(decode::decode_market_status(&data).unwrap(), MarketStatus::Closed);
+}
+
+#[test]
+fn test_invalid_trading_schedule() {
+    let (mut ctx, _) = setup();
+    let schedule = TradingSchedule {
+        windows: vec![SessionWindow { day: 7, open_minute: 0, close_minute: 60 }],
+        holidays: vec![],
+    };
+
+    let result = ctx.send_as_authority(&[instructions::set_trading_schedule(ctx.authority.pubkey(), SYMBOL, schedule)]);
+    assert_oracle_error(result, 0, OracleError::InvalidTradingSchedule);
+}
+
+#[test]
+fn test_initialize_config_max_size() {
+    let mut ctx = TestContext::new();
+    let symbol = format!("{}/USD", "X".repeat(MAX_SYMBOL_LEN - 4));
+    let feed = ctx.set_pyth_account(2 * ONE_DOLLAR, 0, START_TIME);
+    ctx.init_config(&symbol, TestContext::default_params(feed));
+
+    // Every Vec filled to its limit must still serialize
+    let keys = |n| (0..n).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let schedule = TradingSchedule {
+        windows: (0..MAX_SESSION_WINDOWS as u8)
+            .map(|i| SessionWindow { day: i % 7, open_minute: 0, close_minute: 1_440 })
+            .collect(),
+        holidays: (0..MAX_HOLIDAYS as i64)
+            .map(|date| HolidayOverride { date, open_minute: 0, close_minute: 0 })
+            .collect(),
+    };
+    let authority = ctx.authority.pubkey();
+    ctx.send_as_authority(&[
+        instructions::set_publishers(authority, &symbol, keys(MAX_PUBLISHERS)),
+        instructions::set_attestation_signers(authority, &symbol, keys(MAX_ATTESTATION_SIGNERS), 1),
+        instructions::set_trading_schedule(authority, &symbol, schedule),
+    ])
+    .unwrap();
+
+    let account = ctx.svm.get_account(&pda::find_config_address(&symbol).0).unwrap();
+    assert_eq!(account.data.len(), OracleConfig::SPACE);
+    let config = decode::decode_oracle_config(&account.data).unwrap();
+    assert_eq!(config.symbol, symbol);
+    assert_eq!(config.publishers.len(), MAX_PUBLISHERS);
+    assert_eq!(config.attestation_signers.len(), MAX_ATTESTATION_SIGNERS);
+    assert_eq!(config.trading_schedule.windows.len(), MAX_SESSION_WINDOWS);
+    assert_eq!(config.trading_schedule.holidays.len(), MAX_HOLIDAYS);
+}
+
+/// Write a config account in the first deployed layout
+fn set_legacy_config(ctx: &mut TestContext, symbol: &str, pyth_feed: Pubkey) {
+    const LEGACY_SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 32 + 1;
+    let (address, bump) = pda::find_config_address(symbol);
+
+    let mut data = OracleConfig::DISCRIMINATOR.to_vec();
+    data.extend((symbol.len() as u32).to_le_bytes());
+    data.extend(symbol.as_bytes());
+    data.extend(pyth_feed.to_bytes());
+    data.extend(Pubkey::new_unique().to_bytes());
+    data.extend(MAX_STALENESS.to_le_bytes());
+    data.extend(MAX_CONFIDENCE_BPS.to_le_bytes());
+    data.extend(MAX_DEVIATION_BPS.to_le_bytes());
+    data.extend(ctx.authority.pubkey().to_bytes());
+    data.push(bump);
+    data.resize(LEGACY_SPACE, 0);
+
+    ctx.svm
+        .set_account(address, Account {
+            lamports: ctx.svm.minimum_balance_for_rent_exemption(LEGACY_SPACE),
+            data,
+            owner: oracle_price_feed::ID,
+            executable: false,
+            rent_epoch: 0,
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_migrate_legacy_config() {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(2 * ONE_DOLLAR, 0, START_TIME);
+    set_legacy_config(&mut ctx, SYMBOL, feed);
+
+    // The legacy layout doesn't load until migrated
+    assert!(ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]).is_err());
+
+    let intruder = ctx.funded_keypair();
+    let result = ctx.send(&[instructions::migrate_config(intruder.pubkey(), SYMBOL, EXPO)], &[&intruder]);
+    assert!(result.is_err());
+
+    ctx.send_as_authority(&[instructions::migrate_config(ctx.authority.pubkey(), SYMBOL, EXPO)]).unwrap();
+
+    let account = ctx.svm.get_account(&pda::find_config_address(SYMBOL).0).unwrap();
+    assert_eq!(account.data.len(), OracleConfig::SPACE);
+    assert!(account.lamports >= ctx.svm.minimum_balance_for_rent_exemption(OracleConfig::SPACE));
+
+    let config = decode::decode_oracle_config(&account.data).unwrap();
+    assert_eq!(config.symbol, SYMBOL);
+    assert_eq!(config.pyth_feed, feed);
+    assert_eq!(config.max_staleness, MAX_STALENESS);
+    assert_eq!(config.authority, ctx.authority.pubkey());
+    assert_eq!((config.min_price, config.max_price), (i64::MIN, i64::MAX));
+    assert_eq!(config.expo, EXPO);
+    assert!(config.publishers.is_empty());
+    assert!(!config.allow_negative_prices);
+
+    ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]).unwrap();
+
+    let result = ctx.send_as_authority(&[instructions::migrate_config(ctx.authority.pubkey(), SYMBOL, EXPO)]);
+    assert_oracle_error(result, 0, OracleError::ConfigAlreadyMigrated);
+}
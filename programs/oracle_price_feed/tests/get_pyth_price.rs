@@ -0,0 +1,145 @@
+mod common;
+
+use common::*;
+use oracle_price_feed::{OracleError, PriceSource};
+use oracle_price_feed_client::{decode, instructions};
+
+const SYMBOL: &str = "BTC/USD";
+
+fn setup(price: i64, conf: u64, publish_time: i64) -> (TestContext, solana_sdk::pubkey::Pubkey) {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(price, conf, publish_time);
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+    (ctx, feed)
+}
+
+#[test]
+fn test_happy_path() {
+    // $50,000 ± $25 (5 bps), published 5 seconds ago
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, 25 * ONE_DOLLAR as u64, START_TIME - 5);
+
+    let data = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]).unwrap();
+    let price = decode::decode_price_data(&data).unwrap();
+
+    assert_eq!(price.price, 50_000 * ONE_DOLLAR);
+    assert_eq!(price.confidence, 25 * ONE_DOLLAR as u64);
+    assert_eq!(price.expo, EXPO);
+    assert_eq!(price.timestamp, START_TIME - 5);
+    assert_eq!(price.source, PriceSource::Pyth);
+}
+
+#[test]
+fn test_stale_price() {
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, 25 * ONE_DOLLAR as u64, START_TIME - MAX_STALENESS - 1);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::StalePriceData);
+}
+
+#[test]
+fn test_staleness_boundary_is_inclusive() {
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, 25 * ONE_DOLLAR as u64, START_TIME - MAX_STALENESS);
+
+    assert!(ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]).is_ok());
+}
+
+#[test]
+fn test_low_confidence() {
+    // ± $1,000 on $50,000 = 200 bps > 100 bps
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, 1_000 * ONE_DOLLAR as u64, START_TIME);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_zero_price() {
+    // Zero is rejected outright rather than reported as "0 bps"
+    let (mut ctx, feed) = setup(0, 0, START_TIME);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::ZeroPrice);
+}
+
+#[test]
+fn test_negative_price() {
+    let (mut ctx, feed) = setup(-50_000 * ONE_DOLLAR, 0, START_TIME);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::NegativePrice);
+}
+
+#[test]
+fn test_price_out_of_bounds() {
+    // $0.01 with tight confidence passes every relative check
+    let (mut ctx, feed) = setup(ONE_DOLLAR / 100, 0, START_TIME);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::PriceOutOfBounds);
+}
+
+#[test]
+fn test_garbage_account() {
+    let mut ctx = TestContext::new();
+    let feed = solana_sdk::pubkey::Pubkey::new_unique();
+    ctx.svm
+        .set_account(feed, solana_sdk::account::Account {
+            lamports: 1_000_000_000,
+            data: vec![0; 64],
+            owner: solana_sdk::pubkey::Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        })
+        .unwrap();
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::InvalidPriceFeed);
+}
+
+#[test]
+fn test_max_confidence_does_not_overflow() {
+    // u64::MAX × 10,000 only fits in the u128 intermediate
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, u64::MAX, START_TIME);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_extreme_prices() {
+    let (mut ctx, feed) = setup(i64::MIN, 0, START_TIME);
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::NegativePrice);
+
+    let (mut ctx, feed) = setup(i64::MAX, u64::MAX, START_TIME);
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::ConfidenceTooLarge);
+
+    let (mut ctx, feed) = setup(i64::MAX, 0, START_TIME);
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::PriceOutOfBounds);
+}
+
+#[test]
+fn test_extreme_publish_time() {
+    // Age saturates instead of overflowing
+    let (mut ctx, feed) = setup(50_000 * ONE_DOLLAR, 25 * ONE_DOLLAR as u64, i64::MIN);
+
+    let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+    assert_oracle_error(result, 0, OracleError::StalePriceData);
+}
+
+#[test]
+fn test_extreme_exponents() {
+    // Bounds are in the config's exponent; any other exponent is rejected
+    // rather than compared as if it were the same scale
+    for expo in [i32::MIN, EXPO + 3, i32::MAX] {
+        let mut ctx = TestContext::new();
+        let feed = ctx.set_pyth_account_with_expo(50_000 * ONE_DOLLAR, 25 * ONE_DOLLAR as u64, expo, START_TIME);
+        ctx.init_config(SYMBOL, TestContext::default_params(feed));
+
+        let result = ctx.send_as_authority(&[instructions::get_pyth_price(SYMBOL, feed)]);
+        assert_oracle_error(result, 0, OracleError::ExponentMismatch);
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use oracle_price_feed::OracleError;
+use oracle_price_feed_client::{decode, instructions, pda};
+use solana_sdk::signature::{Keypair, Signer};
+
+const SYMBOL: &str = "BTC/USD";
+
+fn setup() -> (TestContext, Keypair) {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(50_000 * ONE_DOLLAR, 0, START_TIME);
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+
+    let keeper = ctx.funded_keypair();
+    ctx.send_as_authority(&[instructions::set_publishers(ctx.authority.pubkey(), SYMBOL, vec![keeper.pubkey()])])
+        .unwrap();
+
+    (ctx, keeper)
+}
+
+fn submit(ctx: &mut TestContext, keeper: &Keypair, price: i64, confidence: u64) {
+    ctx.send(&[instructions::submit_price(keeper.pubkey(), SYMBOL, price, confidence, EXPO)], &[keeper])
+        .unwrap();
+}
+
+/// Consensus of two fresh Pyth prices and the keeper's submission
+fn validate_with_keeper(ctx: &mut TestContext) -> Result<Vec<u8>, solana_sdk::transaction::TransactionError> {
+    let now = ctx.now();
+    let prices = vec![
+        pyth_price(50_000 * ONE_DOLLAR, 10 * ONE_DOLLAR as u64, now),
+        pyth_price(50_010 * ONE_DOLLAR, 10 * ONE_DOLLAR as u64, now),
+    ];
+    ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, true)])
+}
+
+#[test]
+fn test_stale_submission() {
+    let (mut ctx, keeper) = setup();
+    submit(&mut ctx, &keeper, 50_020 * ONE_DOLLAR, 10 * ONE_DOLLAR as u64);
+
+    // The keeper stopped submitting
+    ctx.set_time(START_TIME + MAX_STALENESS + 1);
+    assert_oracle_error(validate_with_keeper(&mut ctx), 0, OracleError::StalePriceData);
+}
+
+#[test]
+fn test_low_confidence_submission() {
+    let (mut ctx, keeper) = setup();
+    // ± $1,000 on $50,000 = 200 bps
+    submit(&mut ctx, &keeper, 50_020 * ONE_DOLLAR, 1_000 * ONE_DOLLAR as u64);
+
+    assert_oracle_error(validate_with_keeper(&mut ctx), 0, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_deviating_submission() {
+    let (mut ctx, keeper) = setup();
+    // 2% above the Pyth prices
+    submit(&mut ctx, &keeper, 51_000 * ONE_DOLLAR, 10 * ONE_DOLLAR as u64);
+
+    assert_oracle_error(validate_with_keeper(&mut ctx), 0, OracleError::PriceDeviationTooLarge);
+}
+
+#[test]
+fn test_extreme_submission() {
+    let (mut ctx, keeper) = setup();
+
+    // Stored as submitted; consensus rejects it without overflowing
+    submit(&mut ctx, &keeper, i64::MAX, u64::MAX);
+    let account = ctx.svm.get_account(&pda::find_publisher_price_address(SYMBOL).0).unwrap();
+    let stored = decode::decode_publisher_price(&account.data).unwrap();
+    assert_eq!((stored.price, stored.confidence), (i64::MAX, u64::MAX));
+    assert_oracle_error(validate_with_keeper(&mut ctx), 0, OracleError::ConfidenceTooLarge);
+
+    submit(&mut ctx, &keeper, i64::MIN, 0);
+    assert_oracle_error(validate_with_keeper(&mut ctx), 0, OracleError::NegativePrice);
+}
@@ -0,0 +1,266 @@
+mod common;
+
+use common::*;
+use oracle_price_feed::{OracleError, PriceData, PriceSource};
+use oracle_price_feed_client::{decode, instructions};
+use solana_sdk::signature::Signer;
+
+const SYMBOL: &str = "BTC/USD";
+
+fn setup() -> TestContext {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(50_000 * ONE_DOLLAR, 0, START_TIME);
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+    ctx
+}
+
+fn dollars(amount: i64) -> i64 {
+    amount * ONE_DOLLAR
+}
+
+#[test]
+fn test_happy_path_odd_count() {
+    let mut ctx = setup();
+    let switchboard = switchboard_price(&switchboard_account_bytes(50_010.0, 5.0, START_TIME - 2));
+    let prices = vec![
+        pyth_price(dollars(50_000), dollars(10) as u64, START_TIME - 1),
+        switchboard,
+        pyth_price(dollars(49_990), dollars(10) as u64, START_TIME),
+    ];
+
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)])
+        .unwrap();
+    let consensus = decode::decode_price_data(&data).unwrap();
+
+    // Median of [49,990, 50,000, 50,010]
+    assert_eq!(consensus.price, dollars(50_000));
+    assert_eq!(consensus.source, PriceSource::Pyth);
+}
+
+#[test]
+fn test_happy_path_even_count() {
+    let mut ctx = setup();
+    let switchboard = switchboard_price(&switchboard_account_bytes(50_100.0, 5.0, START_TIME - 3));
+    let prices = vec![pyth_price(dollars(50_000), dollars(10) as u64, START_TIME), switchboard];
+
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)])
+        .unwrap();
+    let consensus = decode::decode_price_data(&data).unwrap();
+
+    assert_eq!(consensus.price, dollars(50_050));
+    assert_eq!(consensus.timestamp, START_TIME);
+    assert_eq!(consensus.source, PriceSource::Internal);
+}
+
+#[test]
+fn test_empty_prices() {
+    let mut ctx = setup();
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, vec![], false)]);
+    assert_oracle_error(result, 0, OracleError::NoPriceData);
+}
+
+#[test]
+fn test_stale_switchboard_round() {
+    let mut ctx = setup();
+    let stale = switchboard_price(&switchboard_account_bytes(50_000.0, 5.0, START_TIME - MAX_STALENESS - 1));
+    let prices = vec![pyth_price(dollars(50_000), dollars(10) as u64, START_TIME), stale];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::StalePriceData);
+}
+
+#[test]
+fn test_low_confidence() {
+    let mut ctx = setup();
+    // Switchboard std deviation of $1,000 on $50,000 = 200 bps
+    let wide = switchboard_price(&switchboard_account_bytes(50_000.0, 1_000.0, START_TIME));
+    let prices = vec![pyth_price(dollars(50_000), dollars(10) as u64, START_TIME), wide];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_deviating_source() {
+    let mut ctx = setup();
+    // 2% away from the other two
+    let prices = vec![
+        pyth_price(dollars(50_000), dollars(10) as u64, START_TIME),
+        pyth_price(dollars(50_010), dollars(10) as u64, START_TIME),
+        switchboard_price(&switchboard_account_bytes(51_000.0, 5.0, START_TIME)),
+    ];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::PriceDeviationTooLarge);
+}
+
+#[test]
+fn test_zero_price() {
+    let mut ctx = setup();
+    let prices = vec![pyth_price(0, 0, START_TIME)];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::ZeroPrice);
+}
+
+#[test]
+fn test_negative_prices_behind_flag() {
+    let mut ctx = setup();
+    let authority = ctx.authority.pubkey();
+    // Spread market: -$50 ± $0.10
+    let prices = || {
+        vec![
+            pyth_price(dollars(-50), ONE_DOLLAR as u64 / 10, START_TIME),
+            pyth_price(dollars(-50), ONE_DOLLAR as u64 / 10, START_TIME),
+        ]
+    };
+    ctx.send_as_authority(&[instructions::set_price_bounds(authority, SYMBOL, dollars(-1_000), dollars(1_000))])
+        .unwrap();
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices(), false)]);
+    assert_oracle_error(result, 0, OracleError::NegativePrice);
+
+    ctx.send_as_authority(&[instructions::set_allow_negative_prices(authority, SYMBOL, true)])
+        .unwrap();
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices(), false)])
+        .unwrap();
+    assert_eq!(decode::decode_price_data(&data).unwrap().price, dollars(-50));
+}
+
+#[test]
+fn test_price_out_of_bounds() {
+    let mut ctx = setup();
+    let prices = vec![
+        pyth_price(ONE_DOLLAR / 100, 0, START_TIME),
+        pyth_price(ONE_DOLLAR / 100, 0, START_TIME),
+    ];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::PriceOutOfBounds);
+}
+
+#[test]
+fn test_prices_at_other_exponent() {
+    let mut ctx = setup();
+    // $50,000 at expo -5 is in range as a raw number, but not the config's scale
+    let prices = [50_000_00000, 50_010_00000]
+        .map(|price| PriceData { expo: -5, ..pyth_price(price, 0, START_TIME) })
+        .to_vec();
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::ExponentMismatch);
+}
+
+#[test]
+fn test_rejects_inline_publisher_price() {
+    let mut ctx = setup();
+    let mut forged = pyth_price(dollars(50_000), 0, START_TIME);
+    forged.source = PriceSource::Publisher;
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, vec![forged], false)]);
+    assert_oracle_error(result, 0, OracleError::UnverifiedPublisherPrice);
+}
+
+#[test]
+fn test_includes_publisher_account() {
+    let mut ctx = setup();
+    let keeper = ctx.funded_keypair();
+    ctx.send_as_authority(&[instructions::set_publishers(
+        ctx.authority.pubkey(),
+        SYMBOL,
+        vec![keeper.pubkey()],
+    )])
+    .unwrap();
+    ctx.send(
+        &[instructions::submit_price(keeper.pubkey(), SYMBOL, dollars(50_020), dollars(10) as u64, EXPO)],
+        &[&keeper],
+    )
+    .unwrap();
+
+    let prices = vec![
+        pyth_price(dollars(50_000), dollars(10) as u64, START_TIME),
+        pyth_price(dollars(50_010), dollars(10) as u64, START_TIME),
+    ];
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, true)])
+        .unwrap();
+
+    // Median of [50,000, 50,010, 50,020 (keeper)]
+    assert_eq!(decode::decode_price_data(&data).unwrap().price, dollars(50_010));
+}
+
+#[test]
+fn test_even_count_median_at_i64_extremes() {
+    let mut ctx = setup();
+    let authority = ctx.authority.pubkey();
+    ctx.send_as_authority(&[instructions::set_price_bounds(authority, SYMBOL, 1, i64::MAX)])
+        .unwrap();
+
+    // The two middle prices sum past i64::MAX; the i128 average doesn't
+    let prices = vec![
+        pyth_price(i64::MAX, 0, START_TIME),
+        pyth_price(i64::MAX - 1, 0, START_TIME),
+    ];
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)])
+        .unwrap();
+    assert_eq!(decode::decode_price_data(&data).unwrap().price, i64::MAX - 1);
+
+    ctx.send_as_authority(&[
+        instructions::set_allow_negative_prices(authority, SYMBOL, true),
+        instructions::set_price_bounds(authority, SYMBOL, i64::MIN, -1),
+    ])
+    .unwrap();
+    let prices = vec![
+        pyth_price(i64::MIN, 0, START_TIME),
+        pyth_price(i64::MIN + 1, 0, START_TIME),
+    ];
+    let data = ctx
+        .send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)])
+        .unwrap();
+    assert_eq!(decode::decode_price_data(&data).unwrap().price, i64::MIN + 1);
+}
+
+#[test]
+fn test_opposite_extremes_average_to_zero() {
+    let mut ctx = setup();
+    let authority = ctx.authority.pubkey();
+    ctx.send_as_authority(&[
+        instructions::set_allow_negative_prices(authority, SYMBOL, true),
+        instructions::set_price_bounds(authority, SYMBOL, i64::MIN, i64::MAX),
+    ])
+    .unwrap();
+
+    // (i64::MIN + i64::MAX) / 2 = 0: rejected, never divided by
+    let prices = vec![pyth_price(i64::MIN, 0, START_TIME), pyth_price(i64::MAX, 0, START_TIME)];
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::ZeroPrice);
+}
+
+#[test]
+fn test_max_confidence_does_not_overflow() {
+    let mut ctx = setup();
+    let prices = vec![
+        pyth_price(dollars(50_000), u64::MAX, START_TIME),
+        pyth_price(dollars(50_000), u64::MAX, START_TIME),
+    ];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_extreme_timestamps() {
+    let mut ctx = setup();
+    let prices = vec![
+        pyth_price(dollars(50_000), dollars(10) as u64, START_TIME),
+        pyth_price(dollars(50_000), dollars(10) as u64, i64::MIN),
+    ];
+
+    let result = ctx.send_as_authority(&[instructions::validate_price_consensus(SYMBOL, prices, false)]);
+    assert_oracle_error(result, 0, OracleError::StalePriceData);
+}
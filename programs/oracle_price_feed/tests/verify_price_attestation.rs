@@ -0,0 +1,210 @@
+mod common;
+
+use common::*;
+use oracle_price_feed::{OracleError, PriceAttestation, PriceSource, MAX_ATTESTATION_CLOCK_SKEW};
+use oracle_price_feed_client::{decode, instructions};
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction_with_signature,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+
+const SYMBOL: &str = "BTC/USD";
+
+fn setup(threshold: u8) -> (TestContext, Vec<Keypair>) {
+    let mut ctx = TestContext::new();
+    let feed = ctx.set_pyth_account(50_000 * ONE_DOLLAR, 0, START_TIME);
+    ctx.init_config(SYMBOL, TestContext::default_params(feed));
+
+    let signers: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    ctx.send_as_authority(&[instructions::set_attestation_signers(
+        ctx.authority.pubkey(),
+        SYMBOL,
+        signers.iter().map(|s| s.pubkey()).collect(),
+        threshold,
+    )])
+    .unwrap();
+
+    (ctx, signers)
+}
+
+fn attestation(timestamp: i64) -> PriceAttestation {
+    PriceAttestation {
+        symbol: SYMBOL.to_string(),
+        price: 50_000 * ONE_DOLLAR,
+        confidence: 10 * ONE_DOLLAR as u64,
+        expo: EXPO,
+        timestamp,
+    }
+}
+
+fn sign(signer: &Keypair, message: &[u8]) -> Instruction {
+    let signature: [u8; 64] = signer.sign_message(message).into();
+    new_ed25519_instruction_with_signature(message, &signature, &signer.pubkey().to_bytes())
+}
+
+#[test]
+fn test_two_of_three() {
+    let (mut ctx, signers) = setup(2);
+    let attestation = attestation(START_TIME - 1);
+    let message = attestation.message().unwrap();
+
+    let data = ctx
+        .send_as_authority(&[
+            sign(&signers[0], &message),
+            sign(&signers[2], &message),
+            instructions::verify_price_attestation(attestation),
+        ])
+        .unwrap();
+    let price = decode::decode_price_data(&data).unwrap();
+
+    assert_eq!(price.price, 50_000 * ONE_DOLLAR);
+    assert_eq!(price.timestamp, START_TIME - 1);
+    assert_eq!(price.source, PriceSource::Attestation);
+}
+
+#[test]
+fn test_insufficient_signatures() {
+    let (mut ctx, signers) = setup(2);
+    let attestation = attestation(START_TIME);
+    let message = attestation.message().unwrap();
+
+    // The same signer twice only counts once
+    let result = ctx.send_as_authority(&[
+        sign(&signers[1], &message),
+        sign(&signers[1], &message),
+        instructions::verify_price_attestation(attestation),
+    ]);
+    assert_oracle_error(result, 2, OracleError::InsufficientAttestationSignatures);
+}
+
+#[test]
+fn test_unknown_signer_ignored() {
+    let (mut ctx, signers) = setup(2);
+    let attestation = attestation(START_TIME);
+    let message = attestation.message().unwrap();
+
+    let result = ctx.send_as_authority(&[
+        sign(&signers[0], &message),
+        sign(&Keypair::new(), &message),
+        instructions::verify_price_attestation(attestation),
+    ]);
+    assert_oracle_error(result, 2, OracleError::InsufficientAttestationSignatures);
+}
+
+#[test]
+fn test_signature_over_different_message() {
+    let (mut ctx, signers) = setup(1);
+    let signed = attestation(START_TIME);
+    let mut submitted = signed.clone();
+    submitted.price *= 2;
+
+    let result = ctx.send_as_authority(&[
+        sign(&signers[0], &signed.message().unwrap()),
+        instructions::verify_price_attestation(submitted),
+    ]);
+    assert_oracle_error(result, 1, OracleError::InsufficientAttestationSignatures);
+}
+
+#[test]
+fn test_stale_attestation() {
+    let (mut ctx, signers) = setup(1);
+    let attestation = attestation(START_TIME - MAX_STALENESS - 1);
+    let message = attestation.message().unwrap();
+
+    let result = ctx.send_as_authority(&[
+        sign(&signers[0], &message),
+        instructions::verify_price_attestation(attestation),
+    ]);
+    assert_oracle_error(result, 1, OracleError::StalePriceData);
+}
+
+#[test]
+fn test_future_attestation() {
+    let (mut ctx, signers) = setup(1);
+
+    // Within the allowed clock skew
+    let within = attestation(START_TIME + MAX_ATTESTATION_CLOCK_SKEW);
+    ctx.send_as_authority(&[
+        sign(&signers[0], &within.message().unwrap()),
+        instructions::verify_price_attestation(within),
+    ])
+    .unwrap();
+
+    let ahead = attestation(START_TIME + MAX_ATTESTATION_CLOCK_SKEW + 1);
+    let result = ctx.send_as_authority(&[
+        sign(&signers[0], &ahead.message().unwrap()),
+        instructions::verify_price_attestation(ahead),
+    ]);
+    assert_oracle_error(result, 1, OracleError::FutureTimestamp);
+}
+
+#[test]
+fn test_low_confidence_attestation() {
+    let (mut ctx, signers) = setup(1);
+    let mut attestation = attestation(START_TIME);
+    // 2% of the price against a 1% limit
+    attestation.confidence = 1_000 * ONE_DOLLAR as u64;
+    let message = attestation.message().unwrap();
+
+    let result = ctx.send_as_authority(&[
+        sign(&signers[0], &message),
+        instructions::verify_price_attestation(attestation),
+    ]);
+    assert_oracle_error(result, 1, OracleError::ConfidenceTooLarge);
+}
+
+#[test]
+fn test_extreme_timestamps() {
+    let (mut ctx, signers) = setup(1);
+
+    // Neither end overflows the age or skew arithmetic
+    for (timestamp, expected) in [
+        (i64::MIN, OracleError::StalePriceData),
+        (i64::MAX, OracleError::FutureTimestamp),
+    ] {
+        let attestation = attestation(timestamp);
+        let result = ctx.send_as_authority(&[
+            sign(&signers[0], &attestation.message().unwrap()),
+            instructions::verify_price_attestation(attestation),
+        ]);
+        assert_oracle_error(result, 1, expected);
+    }
+}
+
+#[test]
+fn test_extreme_price_and_confidence() {
+    let (mut ctx, signers) = setup(1);
+
+    let mut wide = attestation(START_TIME);
+    wide.confidence = u64::MAX;
+    let mut negative = attestation(START_TIME);
+    negative.price = i64::MIN;
+    let mut huge = attestation(START_TIME);
+    huge.price = i64::MAX;
+
+    for (attestation, expected) in [
+        (wide, OracleError::ConfidenceTooLarge),
+        (negative, OracleError::NegativePrice),
+        (huge, OracleError::PriceOutOfBounds),
+    ] {
+        let result = ctx.send_as_authority(&[
+            sign(&signers[0], &attestation.message().unwrap()),
+            instructions::verify_price_attestation(attestation),
+        ]);
+        assert_oracle_error(result, 1, expected);
+    }
+}
+
+#[test]
+fn test_invalid_threshold() {
+    let (mut ctx, signers) = setup(1);
+
+    let result = ctx.send_as_authority(&[instructions::set_attestation_signers(
+        ctx.authority.pubkey(),
+        SYMBOL,
+        signers.iter().map(|s| s.pubkey()).collect(),
+        4,
+    )]);
+    assert_oracle_error(result, 0, OracleError::InvalidAttestationSigners);
+}